@@ -0,0 +1,92 @@
+use std::time;
+
+use crate::{
+    components::ProjectileKind,
+    gfx::pixel::{Color, CYAN, ORANGE, YELLOW},
+};
+
+// Ability definitions, the secondary action slot of a craft
+// - like weapons, an ability is pure data and slots only hold an AbilityKind
+// - every ability ends in a blast (see ExplosiveCpt, DetonationEvent), what
+//   differs is how the blast is delivered
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AbilityEffect {
+    // thrown ahead along heading, detonates on hit or expiry
+    Launch {
+        projectile: ProjectileKind,
+        speed: f32,
+        duration: time::Duration,
+    },
+    // left stationary behind the user, detonates on hit or expiry
+    Drop {
+        projectile: ProjectileKind,
+        duration: time::Duration,
+    },
+    // detonates around the user immediately
+    Pulse,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ability {
+    pub name: &'static str,
+    pub effect: AbilityEffect,
+    pub color: Color,
+    pub cooldown: i32, // ms
+    pub max_ammo: u32,
+    pub hit_damage: i32, // direct hit, for delivered blasts
+    pub blast_radius: f32,
+    pub blast_damage: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum AbilityKind {
+    #[default]
+    Bomb,
+    Mine,
+    ShieldBurst,
+}
+
+impl AbilityKind {
+    pub fn spec(&self) -> Ability {
+        match *self {
+            AbilityKind::Bomb => Ability {
+                name: "bomb",
+                effect: AbilityEffect::Launch {
+                    projectile: ProjectileKind::Bomb,
+                    speed: 150.,
+                    duration: time::Duration::new(1, 200_000_000),
+                },
+                color: ORANGE,
+                cooldown: 1000,
+                max_ammo: 5,
+                hit_damage: 10,
+                blast_radius: 60.,
+                blast_damage: 80,
+            },
+            AbilityKind::Mine => Ability {
+                name: "mine",
+                effect: AbilityEffect::Drop {
+                    projectile: ProjectileKind::Mine,
+                    duration: time::Duration::new(20, 0),
+                },
+                color: YELLOW,
+                cooldown: 2000,
+                max_ammo: 8,
+                hit_damage: 10,
+                blast_radius: 40.,
+                blast_damage: 50,
+            },
+            AbilityKind::ShieldBurst => Ability {
+                name: "shield burst",
+                effect: AbilityEffect::Pulse,
+                color: CYAN,
+                cooldown: 3000,
+                max_ammo: 3,
+                hit_damage: 0,
+                blast_radius: 80.,
+                blast_damage: 40,
+            },
+        }
+    }
+}
@@ -10,7 +10,10 @@ use rand::Rng;
 // - facilitates easy specification of avatars via generation functions
 // - for specifying and exploring the "avatar design spaces"
 
-use crate::{components::*, dev, gfx::pixel::*, LOGICAL_WINDOW_HEIGHT, LOGICAL_WINDOW_WIDTH};
+use crate::{
+    abilities::Ability, components::*, dev, gfx::draw::rotate_body_offset, gfx::pixel::*,
+    util::time::now, weapons::Weapon, LOGICAL_WINDOW_HEIGHT, LOGICAL_WINDOW_WIDTH,
+};
 
 // ArchParticle
 // - particle primitive
@@ -33,6 +36,8 @@ pub fn gen_particle(x: f32, y: f32, vx: f32, vy: f32, color: Color) -> ArchParti
                 secondary: WHITE,
             },
             data: DrawData::Particle,
+            stroke: Stroke::new(),
+            fill: FillMode::Outline,
         },
     )
 }
@@ -58,28 +63,44 @@ pub fn gen_buncha_rng_particles(n: i32) -> Vec<ArchParticle> {
     (0..n).map(|_| gen_particle_rng()).collect()
 }
 
+// ArchEmittedParticle
+// - a particle from a ParticleEmitterCpt, fades over its lifetime, then goes
+pub type ArchEmittedParticle = (TransformCpt, RigidBodyCpt, DrawBodyCpt, ParticleLifetimeCpt);
+
+pub fn gen_emitted_particle(
+    position: Vec2,
+    velocity: Vec2,
+    lifetime: f32,
+    colors: (Color, Color),
+) -> ArchEmittedParticle {
+    let (transform, rigidbody, drawbody) =
+        gen_particle(position.x, position.y, velocity.x, velocity.y, colors.0);
+    (
+        transform,
+        rigidbody,
+        drawbody,
+        ParticleLifetimeCpt {
+            age: 0.,
+            lifetime,
+            colors,
+        },
+    )
+}
+
 pub type ArchOrbitParticle = (TransformCpt, DrawBodyCpt, OrbitParticleCpt);
 // Either orbit something, thus has a relation via radius and angle, has a
 // parent entity with a transformcpt
-// Or not orbitting an object and orbits in place.
+// Or not orbitting an object and orbits a fixed point.
 // Can be used for both effects and projectiles
-
-pub fn gen_unattached_orbiting_particle(
-    x_c: f32,
-    y_c: f32,
-    vx_c: f32,
-    vy_c: f32,
-    r: f32,
-    speed: f32,
+pub fn gen_orbiting_particle(
+    orbit: OrbitParticleCpt,
+    center_position: Vec2,
     color: Color,
 ) -> ArchOrbitParticle {
-    let angle = Theta::new();
-    let dx_from_center = r * angle.get().cos();
-    let dy_from_center = r * angle.get().sin();
     (
         TransformCpt {
-            position: Vec2::new(x_c + dx_from_center, y_c + dx_from_center),
-            heading: angle,
+            position: center_position + orbit.offset(),
+            heading: orbit.angle,
             scale: Vec2::new(1.0, 1.0),
         },
         DrawBodyCpt {
@@ -88,48 +109,264 @@ pub fn gen_unattached_orbiting_particle(
                 secondary: WHITE,
             },
             data: DrawData::Particle,
+            stroke: Stroke::new(),
+            fill: FillMode::Outline,
         },
+        orbit,
+    )
+}
+
+// phase is the starting angle on the orbit, radians
+pub fn gen_unattached_orbiting_particle(
+    x_c: f32,
+    y_c: f32,
+    r: f32,
+    speed: f32,
+    phase: f32,
+    color: Color,
+) -> ArchOrbitParticle {
+    let center = Vec2::new(x_c, y_c);
+    let mut angle = Theta::new();
+    angle.set(phase);
+    gen_orbiting_particle(
         OrbitParticleCpt {
+            center: OrbitCenter::Point(center),
             r,
             speed,
-            attached_to: None,
-            angle: Theta::new(),
+            angle,
+            ..OrbitParticleCpt::new()
         },
+        center,
+        color,
     )
 }
 
+// parent_position places the particle on its orbit from the first frame
 pub fn gen_attached_orbiting_particle(
     attached_to: Entity,
+    parent_position: Vec2,
     r: f32,
     speed: f32,
+    phase: f32,
     color: Color,
 ) -> ArchOrbitParticle {
-    let angle = Theta::new();
-    let dx_from_center = r * angle.get().cos();
-    let dy_from_center = r * angle.get().sin();
-    // todo query attach_to ent to calc proper init transform
+    let mut angle = Theta::new();
+    angle.set(phase);
+    gen_orbiting_particle(
+        OrbitParticleCpt {
+            center: OrbitCenter::Body(attached_to),
+            r,
+            speed,
+            angle,
+            ..OrbitParticleCpt::new()
+        },
+        parent_position,
+        color,
+    )
+}
+
+// ArchOrbitalShield
+// - orbiters that damage what they run into and are used up by it
+// - owned by the body they circle, so it never hits them
+pub type ArchOrbitalShield = (
+    TransformCpt,
+    DrawBodyCpt,
+    OrbitParticleCpt,
+    ProjectileCpt,
+    ParticleColliderCpt,
+);
+
+// n orbiters spread evenly around the owner
+pub fn gen_orbital_shield(
+    owner: Entity,
+    owner_position: Vec2,
+    n: usize,
+    r: f32,
+    speed: f32,
+    hit_damage: i32,
+    color: Color,
+) -> Vec<ArchOrbitalShield> {
+    (0..n)
+        .map(|i| {
+            let phase = i as f32 * nalgebra_glm::two_pi::<f32>() / n as f32;
+            let (transform, drawbody, orbit) =
+                gen_attached_orbiting_particle(owner, owner_position, r, speed, phase, color);
+            (
+                transform,
+                DrawBodyCpt {
+                    data: DrawData::R(2.),
+                    ..drawbody
+                },
+                orbit,
+                ProjectileCpt {
+                    kind: ProjectileKind::Pellet,
+                    owner: Some(owner),
+                    is_friendly: true,
+                    hit_damage,
+                    duration: time::Duration::MAX,
+                    start_time: now(),
+                },
+                ParticleColliderCpt::new(),
+            )
+        })
+        .collect()
+}
+
+// ArchFragment
+// - a broken off piece of a body's lines, tumbles and fades, doesnt collide
+pub type ArchFragment = (
+    TransformCpt,
+    RigidBodyCpt,
+    RotatableBodyCpt,
+    DrawBodyCpt,
+    ParticleLifetimeCpt,
+);
+
+pub fn gen_fragment(
+    position: Vec2,
+    heading: f32,
+    lines: Vec<(Vec2, Vec2)>,
+    velocity: Vec2,
+    spin: f32,
+    lifetime: f32,
+    colors: (Color, Color),
+) -> ArchFragment {
     (
         TransformCpt {
-            position: Vec2::new(0., 0.), // updated to be relative to attached_to entity during orbit particle integration system run, collision afterwards, so no problem
-            heading: angle,
+            position,
+            heading: {
+                let mut theta = Theta::new();
+                theta.set(heading);
+                theta
+            },
             scale: Vec2::new(1.0, 1.0),
         },
+        RigidBodyCpt { velocity },
+        RotatableBodyCpt {
+            rotation_rate: spin,
+        },
         DrawBodyCpt {
             colorbody: ColorBodyCpt {
-                primary: color,
-                secondary: WHITE,
+                primary: colors.0,
+                secondary: colors.0,
             },
-            data: DrawData::Particle,
+            data: DrawData::Lines(lines),
+            stroke: Stroke::new(),
+            fill: FillMode::Outline,
         },
-        OrbitParticleCpt {
-            r,
-            speed,
-            attached_to: Some(attached_to),
-            angle: Theta::new(),
+        ParticleLifetimeCpt {
+            age: 0.,
+            lifetime,
+            colors,
         },
     )
 }
 
+// breaks a dying body into fragments and debris by its DrawData, plus the flash
+pub fn gen_death_effect(
+    death: &DeathEffectCpt,
+    transform: &TransformCpt,
+    velocity: Vec2,
+    drawbody: &DrawBodyCpt,
+) -> (
+    Vec<ArchFragment>,
+    Vec<ArchEmittedParticle>,
+    Option<ArchEffect>,
+) {
+    let mut rng = rand::thread_rng();
+    let center = transform.position;
+    let heading = transform.heading.get();
+    let color = drawbody.colorbody.primary;
+    let colors = death.colors.unwrap_or((color, color.with_alpha(0.)));
+    let inherited = match death.kind {
+        DeathEffectKind::Burst => Vec2::zeros(),
+        DeathEffectKind::Shatter => velocity,
+    };
+    let outward = |direction: Vec2, rng: &mut rand::rngs::ThreadRng| {
+        let direction = if direction == Vec2::zeros() {
+            Vec2::new(1., 0.)
+        } else {
+            direction.normalize()
+        };
+        inherited + direction * in_range(death.speed, rng)
+    };
+
+    // pieces in the body's frame, each around its own midpoint. Unscaled, as
+    // the bodies are drawn
+    let mut pieces: Vec<(Vec2, Vec<(Vec2, Vec2)>)> = vec![];
+    let extent = match &drawbody.data {
+        DrawData::Lines(lines) => {
+            for (a, b) in lines {
+                let mid = (a + b) / 2.;
+                pieces.push((mid, vec![(a - mid, b - mid)]));
+            }
+            lines
+                .iter()
+                .flat_map(|(a, b)| [a.norm(), b.norm()])
+                .fold(0., f32::max)
+        }
+        DrawData::R(r) => {
+            // two chords per arc read as curved at these sizes
+            let step = std::f32::consts::TAU / death.arcs.max(1) as f32;
+            for i in 0..death.arcs {
+                let on_circle = |angle: f32| Vec2::new(angle.cos(), angle.sin()) * *r;
+                let start = i as f32 * step;
+                let (a, m, b) = (
+                    on_circle(start),
+                    on_circle(start + step / 2.),
+                    on_circle(start + step),
+                );
+                pieces.push((m, vec![(a - m, Vec2::zeros()), (Vec2::zeros(), b - m)]));
+            }
+            *r
+        }
+        DrawData::Particle | DrawData::Sprite { .. } => 5.,
+    };
+
+    let fragments: Vec<ArchFragment> = pieces
+        .into_iter()
+        .map(|(mid, lines)| {
+            // placed as the body draws this point, the fragment's lines follow
+            let offset = rotate_body_offset(mid, heading);
+            let velocity = outward(offset, &mut rng);
+            gen_fragment(
+                center + offset,
+                heading,
+                lines,
+                velocity,
+                death.spin * (rng.gen::<f32>() * 2. - 1.),
+                death.duration.1,
+                colors,
+            )
+        })
+        .collect();
+
+    let debris: Vec<ArchEmittedParticle> = (0..death.debris)
+        .map(|_| {
+            let angle = rng.gen::<f32>() * std::f32::consts::TAU;
+            let direction = Vec2::new(angle.cos(), angle.sin());
+            let lifetime = in_range(death.duration, &mut rng);
+            gen_emitted_particle(
+                center + direction * extent * rng.gen::<f32>(),
+                outward(direction, &mut rng),
+                lifetime,
+                colors,
+            )
+        })
+        .collect();
+
+    let flash = death
+        .flash
+        .clone()
+        .map(|kind| gen_effect(kind, center.x, center.y, extent * 2.5, colors.0));
+
+    (fragments, debris, flash)
+}
+
+fn in_range(range: (f32, f32), rng: &mut impl Rng) -> f32 {
+    range.0 + (range.1 - range.0) * rng.gen::<f32>()
+}
+
 // ArchCircloid
 // - embodied circle, collidable
 pub type ArchCircloid = (
@@ -138,6 +375,7 @@ pub type ArchCircloid = (
     DrawBodyCpt,
     CircleColliderCpt,
     HealthCpt,
+    DeathEffectCpt,
 );
 
 pub fn gen_circloid(x: f32, y: f32, vx: f32, vy: f32, r: f32, color: Color) -> ArchCircloid {
@@ -156,9 +394,12 @@ pub fn gen_circloid(x: f32, y: f32, vx: f32, vy: f32, r: f32, color: Color) -> A
                 secondary: Color::RGB(0, 0, 0),
             },
             data: DrawData::R(r),
+            stroke: Stroke::new(),
+            fill: FillMode::Outline,
         },
         CircleColliderCpt { r },
         HealthCpt::new(),
+        DeathEffectCpt::new(DeathEffectKind::Burst),
     )
 }
 
@@ -229,16 +470,75 @@ pub fn gen_projectile(
                 secondary: WHITE,
             },
             data: DrawData::Particle,
+            stroke: Stroke::new(),
+            fill: FillMode::Outline,
         },
         ProjectileCpt {
+            kind: ProjectileKind::Photon,
+            owner: None,
             is_friendly: false,
             hit_damage,
             duration,
-            start_time: time::Instant::now(),
+            start_time: now(),
         },
         ParticleColliderCpt {},
     )
 }
+
+// projectile as fired by a weapon, body and lifetime come from the weapon spec
+pub fn gen_weapon_projectile(
+    weapon: &Weapon,
+    x: f32,
+    y: f32,
+    vx: f32,
+    vy: f32,
+    hit_damage: i32,
+) -> ArchProjectile {
+    let mut projectile = gen_projectile(
+        x,
+        y,
+        vx,
+        vy,
+        weapon.projectile_duration,
+        hit_damage,
+        weapon.color,
+    );
+    projectile.0.heading.set(vy.atan2(vx));
+    projectile.2.data = weapon.projectile.draw_data();
+    projectile.3.kind = weapon.projectile;
+    projectile
+}
+
+// ArchHomingProjectile
+// - projectile that steers toward targets, see HomingCpt
+pub type ArchHomingProjectile = (
+    TransformCpt,
+    RigidBodyCpt,
+    DrawBodyCpt,
+    ProjectileCpt,
+    ParticleColliderCpt,
+    HomingCpt,
+);
+
+pub fn gen_homing_projectile(
+    weapon: &Weapon,
+    x: f32,
+    y: f32,
+    vx: f32,
+    vy: f32,
+    hit_damage: i32,
+) -> ArchHomingProjectile {
+    let (transform, rigidbody, drawbody, projectile, collider) =
+        gen_weapon_projectile(weapon, x, y, vx, vy, hit_damage);
+    (
+        transform,
+        rigidbody,
+        drawbody,
+        projectile,
+        collider,
+        HomingCpt::new(),
+    )
+}
 pub fn gen_projectiles(
     n: i32,
     x: f32,
@@ -274,22 +574,59 @@ pub fn gen_buncha_rng_projectiles(n: i32) -> Vec<ArchProjectile> {
     (0..n).map(|_| gen_projectile_rng_all()).collect()
 }
 
-type PingAnimationArchetype = (PingDrawCpt, ColorBodyCpt, AnimationCpt, TransformCpt);
+// ArchExplosiveProjectile
+// - projectile that detonates on hit or expiry, delivered by abilities
+pub type ArchExplosiveProjectile = (
+    TransformCpt,
+    RigidBodyCpt,
+    DrawBodyCpt,
+    ProjectileCpt,
+    ParticleColliderCpt,
+    ExplosiveCpt,
+);
+
+pub fn gen_explosive_projectile(
+    ability: &Ability,
+    projectile: ProjectileKind,
+    x: f32,
+    y: f32,
+    vx: f32,
+    vy: f32,
+    duration: time::Duration,
+) -> ArchExplosiveProjectile {
+    let (transform, rigidbody, mut drawbody, mut projectile_cpt, collider) =
+        gen_projectile(x, y, vx, vy, duration, ability.hit_damage, ability.color);
+    drawbody.data = projectile.draw_data();
+    projectile_cpt.kind = projectile;
+    (
+        transform,
+        rigidbody,
+        drawbody,
+        projectile_cpt,
+        collider,
+        ExplosiveCpt {
+            radius: ability.blast_radius,
+            damage: ability.blast_damage,
+        },
+    )
+}
+
+// ArchEffect
+// - a short lived visual effect, drawn by kind and despawned after one run
+pub type ArchEffect = (EffectCpt, ColorBodyCpt, AnimationCpt, TransformCpt);
 
-pub fn gen_ping_animation(x: f32, y: f32) -> PingAnimationArchetype {
+pub fn gen_effect(kind: EffectKind, x: f32, y: f32, r: f32, color: Color) -> ArchEffect {
+    let (frame_count, rfps) = kind.timing();
     (
-        PingDrawCpt {
-            gap_factors: [1, 2, 3, 6],
-            r: 3.,
+        EffectCpt { kind, r },
+        ColorBodyCpt {
+            primary: color,
+            secondary: WHITE,
         },
-        ColorBodyCpt::grey(),
         AnimationCpt {
-            frame_count: 4,
-            current_frame: 0,
-            rfps: 0.1,
-            rdt_accum: 0.,
+            rfps,
             repeat_count: 1,
-            is_infinite_repeat: false,
+            ..AnimationCpt::new(frame_count)
         },
         TransformCpt {
             position: Vec2::new(x, y),
@@ -298,3 +635,15 @@ pub fn gen_ping_animation(x: f32, y: f32) -> PingAnimationArchetype {
         },
     )
 }
+
+pub fn gen_ping_animation(x: f32, y: f32) -> ArchEffect {
+    gen_effect(
+        EffectKind::Ping {
+            gap_factors: [1, 2, 3, 6],
+        },
+        x,
+        y,
+        3.,
+        GREY,
+    )
+}
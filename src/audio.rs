@@ -3,7 +3,9 @@ use std::path::PathBuf;
 use audio_manager::{AudioPlayback, SoundEffectName, SoundManager};
 use sfxr::WaveType;
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+use crate::components::ProjectileKind;
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum SoundEffectNames {
     Laser,
     TinyShot,
@@ -36,6 +38,8 @@ impl SoundEffectName for SoundEffectNames {
 
 pub fn load_essential_sound_effects(sm: &mut dyn AudioPlayback) -> Result<(), anyhow::Error> {
     sm.load_source_from_assets(&SoundEffectNames::TinyShot, "tiny_shot.wav")?;
+    sm.load_source_from_assets(&SoundEffectNames::LightShot, "light_shot.wav")?;
+    sm.load_source_from_assets(&SoundEffectNames::MedShot, "med_shoot.wav")?;
     sm.load_source_from_assets(&SoundEffectNames::Scratch, "scratch.wav")?;
     sm.load_source_from_assets(&SoundEffectNames::PhysicalDeath, "physical_death.wav")?;
     sm.load_source_from_assets(&SoundEffectNames::PhysicalHarm, "physical_harm.wav")?;
@@ -46,6 +50,7 @@ pub fn load_essential_sound_effects(sm: &mut dyn AudioPlayback) -> Result<(), an
 
     sm.load_source_from_sfxr_sample(&SoundEffectNames::Laser, Laser::default());
     sm.load_source_from_sfxr_sample(&SoundEffectNames::Photon, Photon::default());
+    sm.load_source_from_sfxr_sample(&SoundEffectNames::MamaMiaShot, MamaMia::default());
     sm.play(&SoundEffectNames::Photon);
     Ok(())
 }
@@ -149,6 +154,24 @@ impl Photon {
     }
 }
 
+// placeholder for the charged heavy shot
+pub struct MamaMia;
+impl MamaMia {
+    pub fn default() -> sfxr::Sample {
+        let mut s = sfxr::Sample::new();
+        s.wave_type = sfxr::WaveType::Noise;
+        s.base_freq = 0.3;
+        s.freq_limit = 0.1;
+        s.freq_ramp = -0.15;
+
+        s.env_attack = 0.;
+        s.env_sustain = 0.3;
+        s.env_decay = 0.4;
+
+        s
+    }
+}
+
 // TODO each avatar has a sound archetype
 // what SoundEffectName maps the a state or action on the avatar
 // e.g.
@@ -159,7 +182,39 @@ impl Photon {
 // the primary fire may be any equippable or temp powerup/modified fire type
 // a sound effect is associated with each fire type
 
-// TODO impl an projectile:soundeffect map, any emitting avatar only knows what
-// projectiles it fires. The projectile knows what sound it is mapped to. A map
-// needs to exist for easy, accessible dev, since the projectile:soundeffect map
-// is a large design space
+// Projectile:SoundEffect map
+// Any emitting avatar only knows what projectiles it fires. The projectile
+// knows what sounds it is mapped to over its lifetime. Kept as one table for
+// easy, accessible dev, since the projectile:soundeffect map is a large design
+// space. Explosives expire in their detonation, on impact too, and sound it
+// with the expire entry
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ProjectileSounds {
+    pub fire: Option<SoundEffectNames>,
+    pub hit: Option<SoundEffectNames>,
+    pub expire: Option<SoundEffectNames>,
+}
+
+pub fn projectile_sounds(kind: ProjectileKind) -> ProjectileSounds {
+    use SoundEffectNames::*;
+    #[rustfmt::skip]
+    let (fire, hit, expire) = match kind {
+        //                          fire               hit            expire
+        ProjectileKind::Photon =>    (Some(Photon),      None,          None),
+        ProjectileKind::Pellet =>    (Some(MedShot),     None,          None),
+        ProjectileKind::Bolt =>      (Some(LightShot),   None,          None),
+        ProjectileKind::Flechette => (Some(TinyShot),    None,          None),
+        ProjectileKind::Plasma =>    (Some(MamaMiaShot), Some(Laser),   Some(TinyShot)),
+        ProjectileKind::Missile =>   (Some(MedShot),     None,          Some(TinyShot)),
+        ProjectileKind::Beam =>      (Some(Laser),       None,          None),
+        ProjectileKind::Bomb =>      (Some(TinyShot),    None,          Some(MamaMiaShot)),
+        ProjectileKind::Mine =>      (None,              None,          Some(MamaMiaShot)),
+    };
+    ProjectileSounds { fire, hit, expire }
+}
+
+// a blast sounds like its explosive expiring, a pulse has none and sounds like a
+// bomb
+pub fn detonation_sound(kind: Option<ProjectileKind>) -> Option<SoundEffectNames> {
+    projectile_sounds(kind.unwrap_or(ProjectileKind::Bomb)).expire
+}
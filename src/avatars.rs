@@ -8,13 +8,19 @@ use std::time;
 use nalgebra_glm::Vec2;
 
 use crate::{
+    abilities::AbilityKind,
     components::{
-        CircleColliderCpt, ColorBodyCpt, DrawBodyCpt, DrawData, HealthCpt, HumanInputCpt,
-        MoveAttributesCpt, ProjectileEmitterCpt, RigidBodyCpt, RotatableBodyCpt,
-        RotationalInputCpt, Theta, TransformCpt,
+        AbilitySlotCpt, AnimationCpt, ArmorCpt, CircleColliderCpt, ColorBodyCpt,
+        CraftActionStateCpt, DeathEffectCpt, DeathEffectKind, DrawBodyCpt, DrawData, EmitTrigger,
+        FillMode, HealthCpt, HumanInputCpt, MindStateCpt, MoveAttributesCpt, ParticleEmitter,
+        ParticleEmitterCpt, ProjectileEmitterCpt, RenderLayer, RenderLayerCpt, RigidBodyCpt,
+        RotatableBodyCpt, RotationalInputCpt, ShieldCpt, Stroke, Theta, TransformCpt,
     },
     gfx::draw_bodies::generate_ship_lines,
-    gfx::pixel::{BLUE, GREEN, WHITE, YELLOW},
+    gfx::pixel::{Color, BLUE, GREEN, GREY, ORANGE, RED, WHITE, YELLOW},
+    gfx::sprite::SpriteNames,
+    util::time::now,
+    weapons::WeaponKind,
     LOGICAL_WINDOW_HEIGHT, LOGICAL_WINDOW_WIDTH,
 };
 
@@ -39,6 +45,8 @@ impl Circloid {
                     secondary: WHITE,
                 },
                 data: DrawData::R(r),
+                stroke: Stroke::new(),
+                fill: FillMode::Outline,
             },
         )
     }
@@ -69,33 +77,41 @@ impl Ship {
             },
             RotationalInputCpt::new(),
             ProjectileEmitterCpt {
-                projectile_speed: 300.,
-                cooldown: 250,
-                projectile_duration: time::Duration::new(0, 3000_000_000),
-                hit_damage: 10,
+                weapon: WeaponKind::PhotonCannon,
                 is_friendly: true,
-                last_emission_time: time::Instant::now(),
+                last_emission_time: now(),
                 intends_to_fire: false,
+                burst_remaining: 0,
+                charge_start: None,
+                beam: None,
+                beam_damage_accum: 0.,
             },
             HealthCpt::new(),
         )
     }
 }
 
+pub type HumanShipComponents = (
+    TransformCpt,
+    RigidBodyCpt,
+    RotatableBodyCpt,
+    MoveAttributesCpt,
+    CircleColliderCpt,
+    RotationalInputCpt,
+    ProjectileEmitterCpt,
+    DrawBodyCpt,
+    HumanInputCpt,
+    HealthCpt,
+    ShieldCpt,
+    CraftActionStateCpt,
+    AbilitySlotCpt,
+    ParticleEmitterCpt,
+    DeathEffectCpt,
+);
+
 pub struct HumanShip;
 impl HumanShip {
-    pub fn new() -> (
-        TransformCpt,
-        RigidBodyCpt,
-        RotatableBodyCpt,
-        MoveAttributesCpt,
-        CircleColliderCpt,
-        RotationalInputCpt,
-        ProjectileEmitterCpt,
-        DrawBodyCpt,
-        HumanInputCpt,
-        HealthCpt,
-    ) {
+    pub fn new() -> HumanShipComponents {
         (
             TransformCpt {
                 position: Vec2::new(25., LOGICAL_WINDOW_HEIGHT / 2.0),
@@ -107,16 +123,141 @@ impl HumanShip {
             MoveAttributesCpt::new(),
             CircleColliderCpt { r: 15.0 },
             RotationalInputCpt::new(),
-            ProjectileEmitterCpt::new(),
+            ProjectileEmitterCpt {
+                is_friendly: true,
+                ..ProjectileEmitterCpt::new()
+            },
             DrawBodyCpt {
                 colorbody: ColorBodyCpt {
                     primary: GREEN,
                     secondary: BLUE,
                 },
                 data: DrawData::Lines(generate_ship_lines()),
+                stroke: Stroke::smooth(),
+                fill: FillMode::Filled,
             },
             HumanInputCpt {},
             HealthCpt::new(),
+            ShieldCpt::new(),
+            CraftActionStateCpt::new(),
+            AbilitySlotCpt::new(AbilityKind::Bomb),
+            ParticleEmitterCpt::new(vec![
+                // exhaust out of the notch at the back
+                ParticleEmitter {
+                    rate: 60.,
+                    offset: Vec2::new(-7.5, 0.),
+                    direction: std::f32::consts::PI,
+                    spread: 0.6,
+                    speed: (40., 90.),
+                    lifetime: (0.2, 0.45),
+                    colors: (ORANGE, RED.with_alpha(0.)),
+                    inherit_velocity: 0.3,
+                    ..ParticleEmitter::new(EmitTrigger::WhileThrusting)
+                },
+                // sparks off the hull where hit
+                ParticleEmitter {
+                    spread: 1.6,
+                    speed: (60., 160.),
+                    lifetime: (0.15, 0.35),
+                    colors: (YELLOW, ORANGE.with_alpha(0.)),
+                    inherit_velocity: 1.,
+                    ..ParticleEmitter::new(EmitTrigger::OnHit { count: 8 })
+                },
+            ]),
+            DeathEffectCpt::new(DeathEffectKind::Shatter),
+        )
+    }
+}
+
+// slowly spinning sprite marker, pulsing through its sheet
+pub struct Beacon;
+impl Beacon {
+    pub fn new(
+        x: f32,
+        y: f32,
+    ) -> (
+        TransformCpt,
+        RotatableBodyCpt,
+        DrawBodyCpt,
+        AnimationCpt,
+        RenderLayerCpt,
+    ) {
+        (
+            TransformCpt {
+                position: Vec2::new(x, y),
+                heading: Theta::new(),
+                scale: Vec2::new(2., 2.),
+            },
+            RotatableBodyCpt { rotation_rate: 0.5 },
+            DrawBodyCpt {
+                data: DrawData::Sprite {
+                    sheet: SpriteNames::Beacon,
+                    frame: 0,
+                },
+                ..DrawBodyCpt::new()
+            },
+            AnimationCpt {
+                rfps: 0.15,
+                is_infinite_repeat: true,
+                ..AnimationCpt::new(4)
+            },
+            RenderLayerCpt {
+                layer: RenderLayer::Background,
+            },
+        )
+    }
+}
+
+// drifting body that drops mines when a human craft comes near
+pub struct MineLayer;
+impl MineLayer {
+    pub fn new() -> (
+        TransformCpt,
+        RigidBodyCpt,
+        CircleColliderCpt,
+        DrawBodyCpt,
+        HealthCpt,
+        ArmorCpt,
+        MindStateCpt,
+        CraftActionStateCpt,
+        AbilitySlotCpt,
+    ) {
+        let r = 12.;
+        (
+            TransformCpt {
+                position: Vec2::new(LOGICAL_WINDOW_WIDTH * 0.75, LOGICAL_WINDOW_HEIGHT / 4.),
+                heading: Theta::new(),
+                scale: Vec2::new(1.0, 1.0),
+            },
+            RigidBodyCpt {
+                velocity: Vec2::new(40., 25.),
+            },
+            CircleColliderCpt { r },
+            DrawBodyCpt {
+                colorbody: ColorBodyCpt {
+                    primary: GREY,
+                    secondary: Color::RGB(72, 72, 24),
+                },
+                data: DrawData::R(r),
+                stroke: Stroke {
+                    width: 2.,
+                    is_antialiased: true,
+                },
+                fill: FillMode::Filled,
+            },
+            HealthCpt::new(),
+            // plated against shots, soft to energy
+            ArmorCpt {
+                physical: 0.5,
+                energy: 0.,
+                explosive: 0.25,
+            },
+            MindStateCpt {
+                sense_range: 200.,
+                ..MindStateCpt::new()
+            },
+            CraftActionStateCpt::new(),
+            AbilitySlotCpt::new(AbilityKind::Mine),
         )
     }
 }
@@ -1,6 +1,11 @@
 use crate::{
+    abilities::AbilityKind,
     audio::SoundEffectNames,
+    gfx::draw::rotate_body_offset,
     gfx::pixel::{Color, BLUE, CYAN, GREEN, GREY, MAGENTA, ORANGE, RED, WHITE, YELLOW},
+    gfx::sprite::SpriteNames,
+    util::time::now,
+    weapons::WeaponKind,
     LOGICAL_WINDOW_HEIGHT, LOGICAL_WINDOW_WIDTH,
 };
 use hecs::Entity;
@@ -21,8 +26,50 @@ impl TransformCpt {
             scale: Vec2::new(1., 1.),
         }
     }
+    // t of the way from self to other, 0 is self and 1 is other
+    pub fn lerp(&self, other: &TransformCpt, t: f32) -> TransformCpt {
+        TransformCpt {
+            position: self.position + (other.position - self.position) * t,
+            heading: self.heading.lerp(&other.heading, t),
+            scale: self.scale + (other.scale - self.scale) * t,
+        }
+    }
+}
+
+// Rides on a parent body. The child's TransformCpt is derived each tick from
+// the parent's: offset is in the parent's body frame, turned with its heading
+// as its lines are drawn, rotation is added to its heading. The child keeps
+// its own scale
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParentCpt {
+    pub parent: Entity,
+    pub offset: Vec2,
+    pub rotation: f32,
+    pub on_parent_despawn: OrphanPolicy,
+}
+impl ParentCpt {
+    pub fn new(parent: Entity) -> Self {
+        Self {
+            parent,
+            offset: Vec2::new(0., 0.),
+            rotation: 0.,
+            on_parent_despawn: OrphanPolicy::Despawn,
+        }
+    }
+}
+
+// What happens to a child once its parent is gone
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum OrphanPolicy {
+    #[default]
+    Despawn, // along with its own children
+    Detach, // stays where it was and moves on its own
 }
 
+// TransformCpt as of the previous update tick, render interpolates from it
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct PreviousTransformCpt(pub TransformCpt);
+
 #[derive(Clone, Copy, Debug, PartialEq, Default)]
 pub struct Theta(f32);
 impl Theta {
@@ -51,6 +98,12 @@ impl Theta {
     pub fn set(&mut self, x: f32) {
         self.0 = x;
     }
+    // turns the short way round, so 350° to 10° passes through 0° not 180°
+    pub fn lerp(&self, other: &Theta, t: f32) -> Theta {
+        let pi = nalgebra_glm::pi::<f32>();
+        let diff = (other.0 - self.0 + pi).rem_euclid(2. * pi) - pi;
+        Theta(self.0 + diff * t)
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Default)]
@@ -138,23 +191,46 @@ impl ParticleColliderCpt {
     }
 }
 
+// What an orbiter circles
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OrbitCenter {
+    Point(Vec2),
+    Body(Entity), // once it is gone, see OrbitParticleCpt::on_center_despawn
+}
+
+// Orbits are ellipses about the center, tilted by tilt. The particle's
+// TransformCpt is placed on the orbit each tick, angle is where on it
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct OrbitParticleCpt {
-    pub r: f32,
-    pub speed: f32,
+    pub center: OrbitCenter,
+    pub r: f32,      // along the major axis
+    pub aspect: f32, // minor over major axis, 1 is a circle
+    pub tilt: Theta, // direction of the major axis
+    pub speed: f32,  // px/s along the orbit, negative runs clockwise
     pub angle: Theta,
-    pub attached_to: Option<Entity>,
+    // for a Body center: Despawn goes with it, Detach keeps circling the spot
+    // where it was last
+    pub on_center_despawn: OrphanPolicy,
 }
 
 impl OrbitParticleCpt {
     pub fn new() -> Self {
         Self {
+            center: OrbitCenter::Point(Vec2::new(0., 0.)),
             r: 10.,
+            aspect: 1.,
+            tilt: Theta::new(),
             speed: 50.,
             angle: Theta::new(),
-            attached_to: None,
+            on_center_despawn: OrphanPolicy::Despawn,
         }
     }
+    // from the center to the particle's spot on the orbit
+    pub fn offset(&self) -> Vec2 {
+        let x = self.r * self.angle.cos();
+        let y = self.r * self.aspect * self.angle.sin();
+        rotate_body_offset(Vec2::new(x, y), self.tilt.get())
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Default)]
@@ -252,43 +328,142 @@ impl CraftActionStateCpt {
 pub struct MindStateCpt {
     pub is_sensing: bool,
     pub is_using_primary: bool,
+    pub sense_range: f32,
 }
 impl MindStateCpt {
     pub fn new() -> Self {
         Self {
             is_sensing: false,
             is_using_primary: false,
+            sense_range: 150.,
+        }
+    }
+}
+
+// Secondary action slot, triggered by CraftActionStateCpt::is_firing_secondary
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AbilitySlotCpt {
+    pub ability: AbilityKind,
+    pub ammo: u32,
+    pub last_activation_time: time::Instant,
+}
+impl AbilitySlotCpt {
+    pub fn new(ability: AbilityKind) -> Self {
+        Self {
+            ability,
+            ammo: ability.spec().max_ammo,
+            last_activation_time: now(),
         }
     }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct ProjectileEmitterCpt {
-    pub projectile_speed: f32,
-    pub cooldown: i32,
-    pub projectile_duration: time::Duration,
-    pub hit_damage: i32, // ? better as a DamageOnCollisionCpt ?
+    pub weapon: WeaponKind,
     pub is_friendly: bool,
     pub last_emission_time: time::Instant,
     pub intends_to_fire: bool,
+    pub burst_remaining: u32, // shots left in the current FirePattern::Burst
+    pub charge_start: Option<time::Instant>, // set while a FirePattern::Charge is held
+    pub beam: Option<(Vec2, Vec2)>, // (start, end) while a FirePattern::Beam is held
+    pub beam_damage_accum: f32,
 }
 
 impl ProjectileEmitterCpt {
     pub fn new() -> Self {
         Self {
-            projectile_speed: 300.,
-            cooldown: 100,
-            projectile_duration: time::Duration::new(7, 0),
-            hit_damage: 50,
+            weapon: WeaponKind::PhotonCannon,
             is_friendly: false,
-            last_emission_time: time::Instant::now(),
+            last_emission_time: now(),
             intends_to_fire: true,
+            burst_remaining: 0,
+            charge_start: None,
+            beam: None,
+            beam_damage_accum: 0.,
+        }
+    }
+    pub fn cycle_weapon(&mut self) {
+        self.weapon = self.weapon.next();
+        self.burst_remaining = 0;
+        self.charge_start = None;
+        self.beam = None;
+        self.beam_damage_accum = 0.;
+    }
+}
+
+// Projectile archetypes, decides the projectile's body
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum ProjectileKind {
+    #[default]
+    Photon,
+    Pellet,
+    Bolt,
+    Flechette,
+    Plasma,
+    Missile,
+    Beam, // hitscan, drawn and resolved by the emitter, never spawned
+    Bomb,
+    Mine,
+}
+impl ProjectileKind {
+    pub fn draw_data(&self) -> DrawData {
+        match self {
+            ProjectileKind::Photon
+            | ProjectileKind::Pellet
+            | ProjectileKind::Bolt
+            | ProjectileKind::Flechette
+            | ProjectileKind::Beam => DrawData::Particle,
+            ProjectileKind::Plasma => DrawData::R(2.),
+            ProjectileKind::Bomb => DrawData::R(3.),
+            ProjectileKind::Mine => DrawData::R(4.),
+            // short line along heading
+            ProjectileKind::Missile => {
+                DrawData::Lines(vec![(Vec2::new(-4., 0.), Vec2::new(2., 0.))])
+            }
+        }
+    }
+    pub fn damage_type(&self) -> DamageType {
+        match self {
+            ProjectileKind::Photon | ProjectileKind::Plasma | ProjectileKind::Beam => {
+                DamageType::Energy
+            }
+            ProjectileKind::Pellet | ProjectileKind::Bolt | ProjectileKind::Flechette => {
+                DamageType::Physical
+            }
+            ProjectileKind::Missile | ProjectileKind::Bomb | ProjectileKind::Mine => {
+                DamageType::Explosive
+            }
+        }
+    }
+}
+
+// Steers a projectile toward a target acquired in a cone ahead of it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HomingCpt {
+    pub turn_rate: f32, // radians per second
+    pub cone: f32,      // half-angle in radians, measured from heading
+    pub range: f32,
+    pub target: Option<Entity>,
+    pub has_acquired: bool,
+    pub is_retargeting: bool, // acquire a new target when the current one is despawned
+}
+impl HomingCpt {
+    pub fn new() -> Self {
+        Self {
+            turn_rate: 4.,
+            cone: nalgebra_glm::quarter_pi::<f32>(),
+            range: 400.,
+            target: None,
+            has_acquired: false,
+            is_retargeting: true,
         }
     }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct ProjectileCpt {
+    pub kind: ProjectileKind,
+    pub owner: Option<Entity>, // never hits its owner
     pub is_friendly: bool,
     pub hit_damage: i32,
     pub duration: time::Duration,
@@ -297,10 +472,12 @@ pub struct ProjectileCpt {
 impl ProjectileCpt {
     pub fn new() -> Self {
         Self {
+            kind: ProjectileKind::Photon,
+            owner: None,
             is_friendly: false,
             hit_damage: 0,
             duration: time::Duration::new(0, 3_000_000_000),
-            start_time: time::Instant::now(),
+            start_time: now(),
         }
     }
 }
@@ -323,6 +500,8 @@ pub enum DrawData {
     R(f32),
     Lines(Vec<(Vec2, Vec2)>),
     Particle,
+    // frame of a loaded sprite sheet, advanced by an AnimationCpt if present
+    Sprite { sheet: SpriteNames, frame: usize },
 }
 
 impl DrawData {
@@ -350,6 +529,8 @@ impl DrawData {
 pub struct DrawBodyCpt {
     pub data: DrawData,
     pub colorbody: ColorBodyCpt,
+    pub stroke: Stroke,
+    pub fill: FillMode,
 }
 
 impl DrawBodyCpt {
@@ -357,6 +538,65 @@ impl DrawBodyCpt {
         DrawBodyCpt {
             colorbody: ColorBodyCpt::new(),
             data: DrawData::new(),
+            stroke: Stroke::new(),
+            fill: FillMode::Outline,
+        }
+    }
+}
+
+// Draw order buckets, lower layers are drawn first and so appear underneath.
+// Bodies without a RenderLayerCpt are on World
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum RenderLayer {
+    Background,
+    #[default]
+    World,
+    Effects,
+    Hud,
+    Debug,
+}
+impl RenderLayer {
+    pub const ALL: [RenderLayer; 5] = [
+        RenderLayer::Background,
+        RenderLayer::World,
+        RenderLayer::Effects,
+        RenderLayer::Hud,
+        RenderLayer::Debug,
+    ];
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct RenderLayerCpt {
+    pub layer: RenderLayer,
+}
+
+// Whether a DrawBodyCpt is solid: Filled paints the inside with the secondary
+// color under the primary outline
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum FillMode {
+    #[default]
+    Outline,
+    Filled,
+}
+
+// How the outlines of a DrawBodyCpt are rasterized. A hard 1px stroke uses the
+// plain primitives, anything else goes through the coverage based ones
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stroke {
+    pub width: f32,
+    pub is_antialiased: bool,
+}
+impl Stroke {
+    pub fn new() -> Self {
+        Self {
+            width: 1.,
+            is_antialiased: false,
+        }
+    }
+    pub fn smooth() -> Self {
+        Self {
+            width: 1.,
+            is_antialiased: true,
         }
     }
 }
@@ -367,22 +607,114 @@ pub struct CollisionDetectionEvent {
     pub b: Entity,
 }
 
+// Blast dealt when the entity is despawned by a hit or by expiring
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExplosiveCpt {
+    pub radius: f32,
+    pub damage: i32,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DetonationEvent {
+    pub position: Vec2,
+    pub radius: f32,
+    pub damage: i32,
+    pub owner: Option<Entity>,        // spared by the blast
+    pub kind: Option<ProjectileKind>, // picks the blast sound
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum DamageType {
+    #[default]
+    Physical,
+    Energy,
+    Explosive,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct PhysicalDamageEvent {
     pub receiver: Entity,
+    pub source: Option<Entity>, // the attacking body, if any
     pub damage: i32,
+    pub kind: DamageType,
+    pub position: Vec2, // where the receiver was hit
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub struct HealthCpt {
     pub hp: i32,
+    pub max_hp: i32,
 }
 impl HealthCpt {
     pub fn new() -> Self {
-        HealthCpt { hp: 100 }
+        HealthCpt {
+            hp: 100,
+            max_hp: 100,
+        }
     }
 }
 
+// Regenerating layer that absorbs damage before HealthCpt
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShieldCpt {
+    pub strength: f32,
+    pub max_strength: f32,
+    pub regen_rate: f32,             // strength per second
+    pub regen_delay: time::Duration, // since last hit
+    pub last_hit_time: time::Instant,
+}
+impl ShieldCpt {
+    pub fn new() -> Self {
+        Self {
+            strength: 50.,
+            max_strength: 50.,
+            regen_rate: 10.,
+            regen_delay: time::Duration::new(2, 0),
+            last_hit_time: now(),
+        }
+    }
+}
+
+// Fraction of damage resisted per damage type, applied before shields
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct ArmorCpt {
+    pub physical: f32,
+    pub energy: f32,
+    pub explosive: f32,
+}
+impl ArmorCpt {
+    pub fn resistance(&self, kind: DamageType) -> f32 {
+        let resistance = match kind {
+            DamageType::Physical => self.physical,
+            DamageType::Energy => self.energy,
+            DamageType::Explosive => self.explosive,
+        };
+        resistance.clamp(0., 1.)
+    }
+}
+
+// Marks an entity as recently damaged, its health bar shows until the overlay
+// fades out
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DamageOverlayCpt {
+    pub last_damage_time: time::Instant,
+}
+impl DamageOverlayCpt {
+    pub const DURATION: time::Duration = time::Duration::new(3, 0);
+}
+
+// Floating number rising from a hit, paired with a TransformCpt at the hit
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DamageNumberCpt {
+    pub value: i32,
+    pub color: Color,
+    pub start_time: time::Instant,
+}
+impl DamageNumberCpt {
+    pub const DURATION: time::Duration = time::Duration::from_millis(1200);
+    pub const RISE_SPEED: f32 = 20.; // pixels per second
+}
+
 pub struct AnimationCpt {
     pub frame_count: usize,
     pub current_frame: usize,
@@ -402,10 +734,287 @@ impl AnimationCpt {
             is_infinite_repeat: false,
         }
     }
+    // 0 at the start of the first frame to 1 at the end of the last
+    pub fn progress(&self) -> f32 {
+        let within_frame = (self.rdt_accum / self.rfps).min(1.);
+        ((self.current_frame as f32 + within_frame) / self.frame_count as f32).min(1.)
+    }
+}
+
+// Tweens animate a component value over time, by data instead of a system per
+// effect. An entity can run several at once, each on its own target
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicOut,
+    SineInOut,
+    BackOut, // overshoots, then settles
+}
+impl Easing {
+    // maps progress in [0, 1] to eased progress, 0 and 1 stay put
+    pub fn ease(&self, t: f32) -> f32 {
+        let t = t.clamp(0., 1.);
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1. - (1. - t) * (1. - t),
+            Easing::QuadInOut => {
+                if t < 0.5 {
+                    2. * t * t
+                } else {
+                    1. - (-2. * t + 2.).powi(2) / 2.
+                }
+            }
+            Easing::CubicOut => 1. - (1. - t).powi(3),
+            Easing::SineInOut => -((std::f32::consts::PI * t).cos() - 1.) / 2.,
+            Easing::BackOut => {
+                let c1 = 1.70158;
+                let c3 = c1 + 1.;
+                1. + c3 * (t - 1.).powi(3) + c1 * (t - 1.).powi(2)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum TweenMode {
+    #[default]
+    Once,
+    Loop,     // restarts from the start
+    PingPong, // runs back and forth, one cycle is one way
+}
+
+// the value a tween drives, from and to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TweenTarget {
+    Color(Color, Color), // DrawBodyCpt primary
+    Alpha(f32, f32),     // DrawBodyCpt primary alpha, 0 to 1
+    Radius(f32, f32),    // DrawData::R bodies
+    Scale(Vec2, Vec2),
+    Offset(Vec2, Vec2), // ParentCpt offset of a child, else moves the position by it
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tween {
+    pub target: TweenTarget,
+    pub duration: f32, // seconds per cycle
+    pub easing: Easing,
+    pub mode: TweenMode,
+    pub repeat_count: Option<u32>, // cycles of Loop and PingPong, None runs forever
+    pub elapsed: f32,
+    pub applied_offset: Vec2, // how far an Offset tween has moved the position so far
+}
+impl Tween {
+    pub fn new(target: TweenTarget, duration: f32) -> Self {
+        Tween {
+            target,
+            duration,
+            easing: Easing::Linear,
+            mode: TweenMode::Once,
+            repeat_count: None,
+            elapsed: 0.,
+            applied_offset: Vec2::new(0., 0.),
+        }
+    }
+    // cycles to run, None for forever
+    fn cycles(&self) -> Option<u32> {
+        match self.mode {
+            TweenMode::Once => Some(1),
+            TweenMode::Loop | TweenMode::PingPong => self.repeat_count,
+        }
+    }
+    pub fn is_finished(&self) -> bool {
+        self.cycles()
+            .is_some_and(|n| self.elapsed >= self.duration * n as f32)
+    }
+    // eased progress from `from` (0) to `to` (1) at the elapsed time
+    pub fn progress(&self) -> f32 {
+        let t = match self.cycles() {
+            _ if self.duration <= 0. => 1.,
+            // a ping-pong of an even number of cycles ends back at from
+            Some(n) if self.is_finished() => match self.mode {
+                TweenMode::PingPong if n % 2 == 0 => 0.,
+                _ => 1.,
+            },
+            _ => {
+                let cycles = self.elapsed / self.duration;
+                match self.mode {
+                    TweenMode::Once => cycles,
+                    TweenMode::Loop => cycles.fract(),
+                    TweenMode::PingPong if (cycles as u32) % 2 == 1 => 1. - cycles.fract(),
+                    TweenMode::PingPong => cycles.fract(),
+                }
+            }
+        };
+        self.easing.ease(t)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct TweenCpt {
+    pub tweens: Vec<Tween>,
+    pub is_despawned_when_done: bool, // once the last tween finishes
+}
+impl TweenCpt {
+    pub fn new(tweens: Vec<Tween>) -> Self {
+        TweenCpt {
+            tweens,
+            is_despawned_when_done: false,
+        }
+    }
+}
+
+// a tween ran its course, finished tweens are dropped from their TweenCpt
+#[derive(Clone, Copy, Debug)]
+pub struct TweenFinishedEvent {
+    pub entity: Entity,
+    pub target: TweenTarget,
+}
+
+// What sets an emitter off
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum EmitTrigger {
+    #[default]
+    Continuous,
+    WhileThrusting, // RotationalInputCpt::is_thrusting
+    OnHit {
+        count: u32,
+    }, // a burst at every hit, away from the body's center
+}
+
+// One source of short lived particles on a body. Angles and the offset are in
+// the body's frame: direction 0 is along its heading, PI emits backwards
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParticleEmitter {
+    pub trigger: EmitTrigger,
+    pub rate: f32,              // particles per second, for continuous triggers
+    pub offset: Vec2,           // spawn point from the body's center
+    pub direction: f32,         // radians off the heading, unused by OnHit
+    pub spread: f32,            // width of the velocity cone, radians
+    pub speed: (f32, f32),      // px/s, min and max
+    pub lifetime: (f32, f32),   // seconds, min and max
+    pub colors: (Color, Color), // at birth and at death, faded between
+    pub inherit_velocity: f32,  // share of the body's velocity passed on
+    pub accumulator: f32,       // particles owed, carried between ticks
+}
+impl ParticleEmitter {
+    pub fn new(trigger: EmitTrigger) -> Self {
+        ParticleEmitter {
+            trigger,
+            rate: 30.,
+            offset: Vec2::new(0., 0.),
+            direction: 0.,
+            spread: 0.5,
+            speed: (50., 100.),
+            lifetime: (0.3, 0.6),
+            colors: (WHITE, WHITE.with_alpha(0.)),
+            inherit_velocity: 0.,
+            accumulator: 0.,
+        }
+    }
 }
 
-pub struct PingDrawCpt {
-    pub gap_factors: [i32; 4],
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct ParticleEmitterCpt {
+    pub emitters: Vec<ParticleEmitter>,
+    pub hits: Vec<Vec2>, // since the last emission, left by damage resolution
+}
+impl ParticleEmitterCpt {
+    pub fn new(emitters: Vec<ParticleEmitter>) -> Self {
+        ParticleEmitterCpt {
+            emitters,
+            hits: vec![],
+        }
+    }
+}
+
+// an emitted particle, ages out and fades from the first color to the second
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParticleLifetimeCpt {
+    pub age: f32,
+    pub lifetime: f32,
+    pub colors: (Color, Color),
+}
+
+// How a body goes when it is killed. Fragments come from its DrawData: a
+// circle breaks into arcs, lines fly apart as they are drawn
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DeathEffectKind {
+    Burst,   // debris and fragments flung out of the center
+    Shatter, // the pieces drift on with the body and tumble
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeathEffectCpt {
+    pub kind: DeathEffectKind,
+    pub debris: u32,                    // particles, on top of the fragments
+    pub arcs: u32,                      // fragments a circle breaks into
+    pub speed: (f32, f32),              // px/s away from the center, min and max
+    pub spin: f32,                      // fastest tumble of a fragment, rad/s
+    pub duration: (f32, f32),           // seconds, min and max
+    pub colors: Option<(Color, Color)>, // None fades the body's own color out
+    pub flash: Option<EffectKind>,      // drawn at the center, sized to the body
+}
+impl DeathEffectCpt {
+    // durations run about as long as the PhysicalDeath and
+    // PlayerPhysicalDeath sounds that play alongside
+    pub fn new(kind: DeathEffectKind) -> Self {
+        let (debris, arcs, speed, spin, duration, flash) = match kind {
+            DeathEffectKind::Burst => (
+                24,
+                6,
+                (40., 160.),
+                6.,
+                (0.6, 1.75),
+                EffectKind::ExplosionRing,
+            ),
+            DeathEffectKind::Shatter => (12, 4, (15., 60.), 4., (1.2, 1.95), EffectKind::Shockwave),
+        };
+        DeathEffectCpt {
+            kind,
+            debris,
+            arcs,
+            speed,
+            spin,
+            duration,
+            colors: None,
+            flash: Some(flash),
+        }
+    }
+}
+
+// Short lived visual effects, one render system draws them all by kind, like
+// bodies by their DrawData. The AnimationCpt alongside paces them: frames step
+// an effect, progress runs smoothly from 0 to 1 over all of its frames
+#[derive(Clone, Debug, PartialEq)]
+pub enum EffectKind {
+    Ping { gap_factors: [i32; 4] }, // dashed rings, widening as the gaps close
+    ExplosionRing,                  // a flash burning out into a widening ring
+    SparkBurst { count: usize },    // streaks flying out from the center
+    Shockwave,                      // a thick ring racing out and thinning
+    TextPop(String),                // text popping up, rising and fading
+}
+impl EffectKind {
+    // (frame count, seconds per frame)
+    pub fn timing(&self) -> (usize, f32) {
+        match self {
+            EffectKind::Ping { .. } => (4, 0.1),
+            EffectKind::ExplosionRing => (10, 0.04),
+            EffectKind::SparkBurst { .. } => (8, 0.04),
+            EffectKind::Shockwave => (12, 0.03),
+            EffectKind::TextPop(_) => (20, 0.05),
+        }
+    }
+}
+
+// r sizes the effect: the first ring of a ping, how far the others reach
+#[derive(Clone, Debug, PartialEq)]
+pub struct EffectCpt {
+    pub kind: EffectKind,
     pub r: f32,
 }
 
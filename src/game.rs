@@ -1,7 +1,7 @@
 #[allow(warnings)]
 use anyhow::{anyhow, Context, Result};
 use audio_manager::{AudioPlayback, SilentAudioPlayback, SoundManager};
-use hecs::{PreparedQuery, With, Without, World};
+use hecs::{Entity, PreparedQuery, With, Without, World};
 use log::info;
 use rand::prelude::*;
 use rodio::cpal::traits::HostTrait;
@@ -17,17 +17,26 @@ use winit::window::Window;
 use winit_input_helper::WinitInputHelper;
 
 use crate::archetypes::{
-    gen_attached_orbiting_particle, gen_buncha_rng_particles, gen_ping_animation,
-    gen_unattached_orbiting_particle,
+    gen_attached_orbiting_particle, gen_buncha_rng_particles, gen_orbital_shield,
+    gen_ping_animation, gen_unattached_orbiting_particle,
 };
 use crate::audio::{load_essential_sound_effects, SoundEffectNames};
 use crate::avatars::{Circloid, HumanShip};
+use crate::gfx::canvas::Canvas;
 use crate::gfx::draw::{draw_arcs, draw_circle, draw_pixel, draw_rect};
-use crate::gfx::draw_bodies::{draw_avatar, draw_boundary, draw_collision_circle};
+use crate::gfx::draw_bodies::{
+    draw_avatar, draw_beam, draw_boundary, draw_collision_circle, draw_shield_bubble,
+};
 use crate::gfx::pixel::*;
+use crate::gfx::postfx::PostFx;
+use crate::gfx::recorder::FrameRecorder;
+use crate::gfx::sprite::{load_essential_sprites, SpriteSheets};
+use crate::gfx::starfield::Starfield;
+use crate::gfx::text::{draw_stroke_text, draw_text, stroke_text_size, text_size};
 use crate::gui::Framework;
 use crate::scenario::{
-    gen_intersecting_particles, gen_row_particles, spawn_scenario1, spawn_scenario_shootingallery,
+    gen_intersecting_particles, gen_row_particles, spawn_scenario1, spawn_scenario_adversaries,
+    spawn_scenario_shootingallery,
 };
 use crate::util::time::{Dt, FrameTimer};
 use crate::{dev, game, log_error, DebugContext, LOGICAL_WINDOW_HEIGHT, LOGICAL_WINDOW_WIDTH}; // little function in main.rs
@@ -95,6 +104,10 @@ pub struct Game {
     pub input: WinitInputHelper,
     pub world: World,
     pub sound_manager: Box<dyn AudioPlayback>,
+    pub sprites: SpriteSheets,
+    pub recorder: FrameRecorder,
+    pub postfx: PostFx,
+    pub starfield: Starfield,
 }
 
 impl GetRunState for Game {
@@ -123,27 +136,52 @@ impl Game {
             input: WinitInputHelper::new(),
             world: World::new(),
             sound_manager,
+            sprites: SpriteSheets::new(),
+            recorder: FrameRecorder::new(),
+            postfx: PostFx::new(),
+            starfield: Starfield::new(),
         })
     }
 
+    // no window, no audio device, for drawing off screen
+    pub fn headless() -> Self {
+        Self {
+            loop_controller: RunController::new(),
+            input: WinitInputHelper::new(),
+            world: World::new(),
+            sound_manager: Box::new(SilentAudioPlayback {}),
+            sprites: SpriteSheets::new(),
+            recorder: FrameRecorder::new(),
+            postfx: PostFx::new(),
+            starfield: Starfield::new(),
+        }
+    }
+
     pub fn setup(&mut self) {
         dev!("SETUP start");
 
         if let Err(e) = load_essential_sound_effects(&mut *self.sound_manager) {
             eprintln!("{e}");
         }
+        if let Err(e) = load_essential_sprites(&mut self.sprites) {
+            eprintln!("{e}");
+        }
 
         let ship = self.world.spawn(HumanShip::new());
 
         // spawn_scenario1(&mut self.world);
         spawn_scenario_shootingallery(&mut self.world);
+        spawn_scenario_adversaries(&mut self.world);
 
         // self.world.spawn(gen_unattached_orbiting_particle(
-        //     300., 300., 100., 100., 25., 200., GREEN,
+        //     300., 300., 25., 200., 0., GREEN,
         // ));
 
-        // self.world
-        //     .spawn(gen_attached_orbiting_particle(ship, 35., 1000., GREEN));
+        // self.world.extend(gen_orbital_shield(
+        //     ship,
+        //     self.world.get::<&TransformCpt>(ship).unwrap().position,
+        //     6, 35., 200., 5, GREEN,
+        // ));
 
         self.loop_controller.run();
         dev!("SETUP fin");
@@ -156,42 +194,153 @@ impl Game {
             return;
         }
 
+        system_remember_transforms(&mut self.world);
         system_process_human_input(&mut self.world, runstate, &self.input);
+        system_process_ai_input(&mut self.world);
         system_projectile_emission(&mut self.world);
+        system_beam_emission(&mut self.world, &dt);
+        system_ability_activation(&mut self.world);
+        system_homing(&mut self.world, &dt);
         system_integrate_rotation(&mut self.world, &dt);
         system_integrate_translation(&mut self.world, &dt);
         system_integrate_orbiting_particles(&mut self.world, &dt);
+        system_tweens(&mut self.world, &dt);
+        system_propagate_transforms(&mut self.world);
+        system_projectile_lifetime(&mut self.world);
+        system_particle_lifetime(&mut self.world, &dt);
+        system_shield_regeneration(&mut self.world, &dt);
+        system_damage_overlay_lifetime(&mut self.world);
         system_boundary_restrict_circloid(&mut self.world);
         system_boundary_restrict_particletypes(&mut self.world);
         test_system_boundary_restrict_particle(&mut self.world);
         system_collision_detection(&mut self.world);
         system_collision_resolution(&mut self.world);
+        system_detonation(&mut self.world);
         system_physical_damage_resolution(&mut self.world);
+        system_particle_emission(&mut self.world, &dt);
+        system_tween_finished_events(&mut self.world);
         system_sound_effects(&mut self.world, &mut *self.sound_manager);
     }
 
-    pub fn render(&mut self, pixels: &mut Pixels, dbg_ctx: &DebugContext, rdt: Dt) {
+    // blend is how far between the last update tick and the next this frame is
+    pub fn render(&mut self, pixels: &mut Pixels, dbg_ctx: &DebugContext, rdt: Dt, blend: f32) {
         if (self.get_runstate() != RunState::Running) && (self.get_runstate() != RunState::Paused) {
             return;
         }
-        let mut frame = pixels.frame_mut();
-        clear(frame);
-        draw_boundary(frame);
+        let mut canvas = Canvas::new(
+            pixels.frame_mut(),
+            LOGICAL_WINDOW_WIDTH as u32,
+            LOGICAL_WINDOW_HEIGHT as u32,
+        );
+        // a paused world does not move on, show it where it stopped
+        let blend = if self.get_runstate() == RunState::Running {
+            blend
+        } else {
+            1.
+        };
+        self.render_to(&mut canvas, dbg_ctx, rdt, blend);
+    }
+
+    // draws the world into any canvas, on screen or off
+    pub fn render_to(&mut self, canvas: &mut Canvas, dbg_ctx: &DebugContext, rdt: Dt, blend: f32) {
+        clear(canvas);
+
+        let ticked = system_interpolate_transforms(&mut self.world, blend);
 
-        for (_id, (transform, drawbody)) in self.world.query_mut::<(&TransformCpt, &DrawBodyCpt)>()
-        {
-            draw_avatar(frame, transform, drawbody);
+        system_sprite_animation(&mut self.world);
+
+        // bucket bodies by layer, hecs iteration order is kept within a layer
+        let bodies: Vec<(RenderLayer, Entity)> = self
+            .world
+            .query_mut::<With<(&DrawBodyCpt, Option<&RenderLayerCpt>), &TransformCpt>>()
+            .into_iter()
+            .map(|(ent, (_drawbody, layer))| (layer.map_or(RenderLayer::World, |l| l.layer), ent))
+            .collect();
+
+        for layer in RenderLayer::ALL {
+            if layer == RenderLayer::Background {
+                // the sky goes under everything, background bodies included
+                let camera = self
+                    .world
+                    .query_mut::<With<&TransformCpt, &HumanInputCpt>>()
+                    .into_iter()
+                    .next()
+                    .map(|(_ent, transform)| transform.position);
+                self.starfield.draw(canvas, camera, &rdt);
+            }
+            for (_layer, ent) in bodies.iter().filter(|(l, _ent)| *l == layer) {
+                if let Ok((transform, drawbody)) = self
+                    .world
+                    .query_one_mut::<(&TransformCpt, &DrawBodyCpt)>(*ent)
+                {
+                    draw_avatar(canvas, transform, drawbody, &self.sprites);
+                }
+            }
+            self.render_layer_overlays(canvas, layer, dbg_ctx);
+            if layer == RenderLayer::Effects {
+                self.postfx.apply(canvas, &rdt);
+            }
         }
 
-        system_render_pings(&mut self.world, &mut frame);
+        system_restore_transforms(&mut self.world, ticked);
         system_animation_lifecycle(&mut self.world, rdt);
+    }
+
+    // whatever is drawn on a layer besides its bodies, after them
+    fn render_layer_overlays(
+        &mut self,
+        canvas: &mut Canvas,
+        layer: RenderLayer,
+        dbg_ctx: &DebugContext,
+    ) {
+        match layer {
+            RenderLayer::Background => draw_boundary(canvas),
+            RenderLayer::World => {}
+            RenderLayer::Effects => {
+                for (_id, (transform, shield, collider)) in
+                    self.world
+                        .query_mut::<(&TransformCpt, &ShieldCpt, &CircleColliderCpt)>()
+                {
+                    draw_shield_bubble(
+                        canvas,
+                        transform,
+                        collider.r,
+                        shield.strength / shield.max_strength,
+                    );
+                }
 
-        if dbg_ctx.is_drawing_collisionareas {
-            for (_id, (transform, collision_circle)) in self
-                .world
-                .query_mut::<(&TransformCpt, &CircleColliderCpt)>()
-            {
-                draw_collision_circle(frame, transform, collision_circle);
+                for (_id, emitter) in self.world.query_mut::<&ProjectileEmitterCpt>() {
+                    if let Some((start, end)) = emitter.beam {
+                        draw_beam(canvas, start, end, emitter.weapon.spec().color);
+                    }
+                }
+
+                system_render_effects(&mut self.world, canvas);
+            }
+            RenderLayer::Hud => {
+                if dbg_ctx.is_drawing_damage_overlay {
+                    system_render_damage_overlay(&mut self.world, canvas);
+                }
+
+                let is_player_alive = self
+                    .world
+                    .query_mut::<&HumanInputCpt>()
+                    .into_iter()
+                    .next()
+                    .is_some();
+                if !is_player_alive {
+                    draw_game_over(canvas);
+                }
+            }
+            RenderLayer::Debug => {
+                if dbg_ctx.is_drawing_collisionareas {
+                    for (_id, (transform, collision_circle)) in self
+                        .world
+                        .query_mut::<(&TransformCpt, &CircleColliderCpt)>()
+                    {
+                        draw_collision_circle(canvas, transform, collision_circle);
+                    }
+                }
             }
         }
     }
@@ -199,9 +348,29 @@ impl Game {
         self.world.clear();
         let ship = self.world.spawn(HumanShip::new());
         spawn_scenario_shootingallery(&mut self.world);
+        spawn_scenario_adversaries(&mut self.world);
     }
 }
 
+fn draw_game_over(canvas: &mut Canvas) {
+    let title = "GAME OVER";
+    let (w, h) = stroke_text_size(title, 8.);
+    let x = (canvas.width() as f32 - w) / 2.;
+    let y = (canvas.height() as f32 - h) / 2.;
+    draw_stroke_text(canvas, x, y, title, 8., WHITE);
+
+    let hint = "press r to restart";
+    let (w, _h) = text_size(hint, 2);
+    draw_text(
+        canvas,
+        (canvas.width() - w) / 2,
+        (y + h) as i32 + 24,
+        hint,
+        2,
+        GREY,
+    );
+}
+
 impl Drop for Game {
     fn drop(&mut self) {
         dev!("Game dropped");
@@ -1,3 +1,11 @@
+pub mod canvas;
 pub mod draw;
 pub mod draw_bodies;
+pub mod draw_effects;
+pub mod image;
 pub mod pixel;
+pub mod postfx;
+pub mod recorder;
+pub mod sprite;
+pub mod starfield;
+pub mod text;
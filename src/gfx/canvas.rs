@@ -0,0 +1,110 @@
+use crate::gfx::pixel::Color;
+
+// Drawing target for everything in gfx
+// - a view over RGBA8 bytes that knows its own width, height and stride (bytes
+//   per row), so draw code never assumes the window's logical size
+// - wraps the pixels frame for the screen, or a PixelBuffer for offscreen
+//   targets (minimap, thumbnails, tests)
+// - coordinates are in pixels from the top-left, out of bounds writes are
+//   dropped here, the single pixel guard for all primitives
+
+pub struct Canvas<'a> {
+    buf: &'a mut [u8],
+    width: i32,
+    height: i32,
+    stride: usize,
+}
+
+impl<'a> Canvas<'a> {
+    // tightly packed rows, as in the pixels frame
+    pub fn new(buf: &'a mut [u8], width: u32, height: u32) -> Self {
+        Self::with_stride(buf, width, height, width as usize * 4)
+    }
+    pub fn with_stride(buf: &'a mut [u8], width: u32, height: u32, stride: usize) -> Self {
+        assert!(stride >= width as usize * 4, "stride shorter than a row");
+        assert!(
+            buf.len() >= stride * height as usize,
+            "buffer smaller than {width}x{height} at stride {stride}"
+        );
+        Canvas {
+            buf,
+            width: width as i32,
+            height: height as i32,
+            stride,
+        }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    #[inline]
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= 0 && x < self.width && y >= 0 && y < self.height
+    }
+
+    // the 4 bytes of a pixel, None when outside the canvas
+    #[inline]
+    pub fn pixel_mut(&mut self, x: i32, y: i32) -> Option<&mut [u8]> {
+        if !self.contains(x, y) {
+            return None;
+        }
+        let i = y as usize * self.stride + x as usize * 4;
+        Some(&mut self.buf[i..i + 4])
+    }
+
+    #[inline]
+    pub fn pixel(&self, x: i32, y: i32) -> Option<Color> {
+        if !self.contains(x, y) {
+            return None;
+        }
+        let i = y as usize * self.stride + x as usize * 4;
+        let p = &self.buf[i..i + 4];
+        Some(Color::RGBA(p[0], p[1], p[2], p[3]))
+    }
+
+    // the visible bytes of row y, without the stride padding
+    pub fn row(&self, y: i32) -> &[u8] {
+        let start = y as usize * self.stride;
+        &self.buf[start..start + self.width as usize * 4]
+    }
+    pub fn row_mut(&mut self, y: i32) -> &mut [u8] {
+        let start = y as usize * self.stride;
+        &mut self.buf[start..start + self.width as usize * 4]
+    }
+
+    pub fn fill(&mut self, color: Color) {
+        for y in 0..self.height {
+            for pixel in self.row_mut(y).chunks_exact_mut(4) {
+                pixel.copy_from_slice(color.as_bytes());
+            }
+        }
+    }
+}
+
+// Owned RGBA8 pixels for drawing off screen
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PixelBuffer {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+impl PixelBuffer {
+    pub fn new(width: u32, height: u32) -> Self {
+        PixelBuffer {
+            width,
+            height,
+            data: vec![0; width as usize * height as usize * 4],
+        }
+    }
+    pub fn canvas(&mut self) -> Canvas<'_> {
+        Canvas::new(&mut self.data, self.width, self.height)
+    }
+}
@@ -1,34 +1,150 @@
-use crate::{components::ColorBodyCpt, dev, gfx::pixel::Color};
+use crate::{
+    components::ColorBodyCpt,
+    dev,
+    gfx::canvas::Canvas,
+    gfx::pixel::{BlendMode, Color},
+};
 
-use crate::{LOGICAL_WINDOW_HEIGHT, LOGICAL_WINDOW_WIDTH};
+use nalgebra_glm::Vec2;
 
-pub fn draw_pixel(frame: &mut [u8], x: i32, y: i32, color: Color) {
-    // this is the pixel guard
-    if x >= 0 && x < (LOGICAL_WINDOW_WIDTH as i32) && y >= 0 && y < LOGICAL_WINDOW_HEIGHT as i32 {
-        let i = ((LOGICAL_WINDOW_WIDTH as i32 * y + x) * 4) as usize;
-        if i < (LOGICAL_WINDOW_WIDTH * LOGICAL_WINDOW_HEIGHT * 4.) as usize {
-            frame[i..i + 4].copy_from_slice(color.as_bytes());
+// every primitive below plots through here, translucent colors blend over
+// what is already in the canvas
+pub fn draw_pixel(canvas: &mut Canvas, x: i32, y: i32, color: Color) {
+    plot(canvas, x, y, color, 1., BlendMode::AlphaOver);
+}
+
+pub fn draw_pixel_blended(canvas: &mut Canvas, x: i32, y: i32, color: Color, mode: BlendMode) {
+    plot(canvas, x, y, color, 1., mode);
+}
+
+// blends color over the canvas pixel, weighted by its alpha and a coverage in
+// [0, 1]. Used by the anti-aliased primitives below
+pub fn blend_pixel(canvas: &mut Canvas, x: i32, y: i32, color: Color, coverage: f32) {
+    plot(canvas, x, y, color, coverage, BlendMode::AlphaOver);
+}
+
+fn plot(canvas: &mut Canvas, x: i32, y: i32, color: Color, coverage: f32, mode: BlendMode) {
+    // the canvas is the pixel guard
+    if let Some(pixel) = canvas.pixel_mut(x, y) {
+        mode.blend(pixel, color, coverage);
+    }
+}
+
+// soft additive light, brightest at the center and falling off to nothing at
+// radius
+pub fn draw_glow(canvas: &mut Canvas, x_center: f32, y_center: f32, radius: f32, color: Color) {
+    let extent = radius.ceil() as i32;
+    let cx = x_center.round() as i32;
+    let cy = y_center.round() as i32;
+    for y in (cy - extent)..=(cy + extent) {
+        for x in (cx - extent)..=(cx + extent) {
+            let d = (x as f32 - x_center).hypot(y as f32 - y_center);
+            if d < radius {
+                let falloff = 1. - d / radius;
+                plot(canvas, x, y, color, falloff * falloff, BlendMode::Additive);
+            }
         }
     }
 }
 
-pub fn draw_rect(frame: &mut [u8], x: i32, y: i32, width: i32, height: i32, color: Color) {
+pub fn draw_rect(canvas: &mut Canvas, x: i32, y: i32, width: i32, height: i32, color: Color) {
     for i in y..=y + height {
         for j in x..=x + width {
-            let n = ((LOGICAL_WINDOW_WIDTH as i32 * i + j) * 4) as usize;
-            if n < (LOGICAL_WINDOW_WIDTH * LOGICAL_WINDOW_HEIGHT * 4.) as usize {
-                if (i == y || i == y + height) && (j >= x && j <= x + width) {
-                    frame[n..n + 4].copy_from_slice(color.as_bytes());
-                }
-                if (j == x || j == x + width) && (i >= y && i <= y + height) {
-                    frame[n..n + 4].copy_from_slice(color.as_bytes());
-                }
+            if i == y || i == y + height || j == x || j == x + width {
+                draw_pixel(canvas, j, i, color);
+            }
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Filled Shapes
+////////////////////////////////////////////////////////////////////////////////
+
+// line segment as its two endpoints
+pub type Edge = ((f32, f32), (f32, f32));
+
+// horizontal run of pixels from x0 to x1 inclusive
+fn draw_span(canvas: &mut Canvas, x0: i32, x1: i32, y: i32, color: Color) {
+    for x in x0..=x1 {
+        draw_pixel(canvas, x, y, color);
+    }
+}
+
+// solid counterpart of draw_rect, covering the same pixels
+pub fn fill_rect(canvas: &mut Canvas, x: i32, y: i32, width: i32, height: i32, color: Color) {
+    for row in y..=y + height {
+        draw_span(canvas, x, x + width, row, color);
+    }
+}
+
+// pixels whose centers lie within radius of the center
+pub fn fill_circle(canvas: &mut Canvas, x_center: f32, y_center: f32, radius: f32, color: Color) {
+    let y_min = (y_center - radius).ceil() as i32;
+    let y_max = (y_center + radius).floor() as i32;
+    for y in y_min..=y_max {
+        let dy = y as f32 - y_center;
+        let half_width = (radius * radius - dy * dy).max(0.).sqrt();
+        draw_span(
+            canvas,
+            (x_center - half_width).ceil() as i32,
+            (x_center + half_width).floor() as i32,
+            y,
+            color,
+        );
+    }
+}
+
+// closed polygon through points, convex or concave, see fill_edges
+pub fn fill_polygon(canvas: &mut Canvas, points: &[(f32, f32)], color: Color) {
+    let edges: Vec<Edge> = points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| (*a, *b))
+        .collect();
+    fill_edges(canvas, &edges, color);
+}
+
+// scanline fill of the area enclosed by edges, by the even-odd rule: a pixel
+// is inside when a ray from it crosses an odd number of edges. Edges need not
+// be ordered, so any set of closed outlines works, holes included
+pub fn fill_edges(canvas: &mut Canvas, edges: &[Edge], color: Color) {
+    if edges.is_empty() {
+        return;
+    }
+    let y_min = edges
+        .iter()
+        .map(|((_, y0), (_, y1))| y0.min(*y1))
+        .fold(f32::MAX, f32::min)
+        .ceil() as i32;
+    let y_max = edges
+        .iter()
+        .map(|((_, y0), (_, y1))| y0.max(*y1))
+        .fold(f32::MIN, f32::max)
+        .floor() as i32;
+
+    let mut crossings: Vec<f32> = vec![];
+    for y in y_min..=y_max {
+        let yc = y as f32;
+        crossings.clear();
+        for ((x0, y0), (x1, y1)) in edges {
+            // half open so shared vertices count once
+            if (*y0 <= yc && yc < *y1) || (*y1 <= yc && yc < *y0) {
+                crossings.push(x0 + (yc - y0) * (x1 - x0) / (y1 - y0));
+            }
+        }
+        crossings.sort_by(|a, b| a.total_cmp(b));
+        for pair in crossings.chunks_exact(2) {
+            let x_start = pair[0].ceil() as i32;
+            let x_end = pair[1].ceil() as i32 - 1;
+            if x_start <= x_end {
+                draw_span(canvas, x_start, x_end, y, color);
             }
         }
     }
 }
 
-pub fn draw_line(frame: &mut [u8], x0: i32, y0: i32, x1: i32, y1: i32, color: Color) {
+pub fn draw_line(canvas: &mut Canvas, x0: i32, y0: i32, x1: i32, y1: i32, color: Color) {
     let x_len = x1 - x0;
     let y_len = y1 - y0;
 
@@ -45,32 +161,201 @@ pub fn draw_line(frame: &mut [u8], x0: i32, y0: i32, x1: i32, y1: i32, color: Co
     let mut y = y0 as f32;
 
     for i in 0..=longer_side_len as i32 {
-        draw_pixel(frame, x.round() as i32, y.round() as i32, color);
+        draw_pixel(canvas, x.round() as i32, y.round() as i32, color);
         x += dx;
         y += dy;
     }
 }
 
-pub fn draw_circle(frame: &mut [u8], x_center: i32, y_center: i32, radius: i32, color: Color) {
+fn fract(x: f32) -> f32 {
+    x - x.floor()
+}
+
+// Xiaolin Wu's line, endpoints at subpixel precision
+pub fn draw_line_aa(canvas: &mut Canvas, x0: f32, y0: f32, x1: f32, y1: f32, color: Color) {
+    let is_steep = (y1 - y0).abs() > (x1 - x0).abs();
+    // walk along the major axis, left to right
+    let (mut x0, mut y0, mut x1, mut y1) = if is_steep {
+        (y0, x0, y1, x1)
+    } else {
+        (x0, y0, x1, y1)
+    };
+    if x0 > x1 {
+        std::mem::swap(&mut x0, &mut x1);
+        std::mem::swap(&mut y0, &mut y1);
+    }
+    let mut plot = |canvas: &mut Canvas, x: i32, y: i32, coverage: f32| {
+        if is_steep {
+            blend_pixel(canvas, y, x, color, coverage);
+        } else {
+            blend_pixel(canvas, x, y, color, coverage);
+        }
+    };
+
+    let dx = x1 - x0;
+    let dy = y1 - y0;
+    let gradient = if dx == 0. { 1. } else { dy / dx };
+
+    // first endpoint
+    let x_end = x0.round();
+    let y_end = y0 + gradient * (x_end - x0);
+    let x_gap = 1. - fract(x0 + 0.5);
+    let x_pxl1 = x_end as i32;
+    let y_pxl1 = y_end.floor() as i32;
+    plot(canvas, x_pxl1, y_pxl1, (1. - fract(y_end)) * x_gap);
+    plot(canvas, x_pxl1, y_pxl1 + 1, fract(y_end) * x_gap);
+    let mut inter_y = y_end + gradient;
+
+    // second endpoint
+    let x_end = x1.round();
+    let y_end = y1 + gradient * (x_end - x1);
+    let x_gap = fract(x1 + 0.5);
+    let x_pxl2 = x_end as i32;
+    let y_pxl2 = y_end.floor() as i32;
+    plot(canvas, x_pxl2, y_pxl2, (1. - fract(y_end)) * x_gap);
+    plot(canvas, x_pxl2, y_pxl2 + 1, fract(y_end) * x_gap);
+
+    // span between, split across the two pixels straddling the ideal line
+    for x in (x_pxl1 + 1)..x_pxl2 {
+        plot(canvas, x, inter_y.floor() as i32, 1. - fract(inter_y));
+        plot(canvas, x, inter_y.floor() as i32 + 1, fract(inter_y));
+        inter_y += gradient;
+    }
+}
+
+// line of any width with round caps, optionally anti-aliased. Pixels are
+// covered by their distance to the segment
+pub fn draw_thick_line(
+    canvas: &mut Canvas,
+    (x0, y0): (f32, f32),
+    (x1, y1): (f32, f32),
+    width: f32,
+    color: Color,
+    is_antialiased: bool,
+) {
+    let half = width / 2.;
+    let a = Vec2::new(x0, y0);
+    let ab = Vec2::new(x1, y1) - a;
+    let len_squared = ab.magnitude_squared();
+
+    let x_min = (x0.min(x1) - half - 1.).floor() as i32;
+    let x_max = (x0.max(x1) + half + 1.).ceil() as i32;
+    let y_min = (y0.min(y1) - half - 1.).floor() as i32;
+    let y_max = (y0.max(y1) + half + 1.).ceil() as i32;
+    for y in y_min..=y_max {
+        for x in x_min..=x_max {
+            let p = Vec2::new(x as f32, y as f32);
+            let t = if len_squared == 0. {
+                0.
+            } else {
+                ((p - a).dot(&ab) / len_squared).clamp(0., 1.)
+            };
+            let d = (p - (a + ab * t)).magnitude();
+            plot_coverage(canvas, x, y, half + 0.5 - d, color, is_antialiased);
+        }
+    }
+}
+
+// Xiaolin Wu's circle, radius at subpixel precision, the center snaps to the
+// nearest pixel. Mirrored octants meet on the axes and diagonals, those pixels
+// are blended once
+pub fn draw_circle_aa(
+    canvas: &mut Canvas,
+    x_center: f32,
+    y_center: f32,
+    radius: f32,
+    color: Color,
+) {
+    let (cx, cy) = (x_center.round() as i32, y_center.round() as i32);
+    let octant_end = (radius / std::f32::consts::SQRT_2).ceil() as i32;
+    for x in 0..=octant_end {
+        let y = (radius * radius - (x * x) as f32).max(0.).sqrt();
+        if x as f32 > y {
+            break;
+        }
+        let inner = y.floor() as i32;
+        let coverage = fract(y);
+        for (px, py, c) in [(x, inner, 1. - coverage), (x, inner + 1, coverage)] {
+            // mirror into all eight octants
+            let mirrored = [
+                (px, py),
+                (-px, py),
+                (px, -py),
+                (-px, -py),
+                (py, px),
+                (-py, px),
+                (py, -px),
+                (-py, -px),
+            ];
+            for (i, (ox, oy)) in mirrored.iter().enumerate() {
+                if !mirrored[..i].contains(&(*ox, *oy)) {
+                    blend_pixel(canvas, cx + ox, cy + oy, color, c);
+                }
+            }
+        }
+    }
+}
+
+// ring of any width, optionally anti-aliased
+pub fn draw_thick_circle(
+    canvas: &mut Canvas,
+    x_center: f32,
+    y_center: f32,
+    radius: f32,
+    width: f32,
+    color: Color,
+    is_antialiased: bool,
+) {
+    let half = width / 2.;
+    let extent = (radius + half + 1.).ceil() as i32;
+    let cx = x_center.round() as i32;
+    let cy = y_center.round() as i32;
+    for y in (cy - extent)..=(cy + extent) {
+        for x in (cx - extent)..=(cx + extent) {
+            let d = ((x as f32 - x_center).hypot(y as f32 - y_center) - radius).abs();
+            plot_coverage(canvas, x, y, half + 0.5 - d, color, is_antialiased);
+        }
+    }
+}
+
+// hard edged shapes take pixels at least half covered
+fn plot_coverage(
+    canvas: &mut Canvas,
+    x: i32,
+    y: i32,
+    coverage: f32,
+    color: Color,
+    is_antialiased: bool,
+) {
+    if is_antialiased {
+        if coverage > 0. {
+            blend_pixel(canvas, x, y, color, coverage);
+        }
+    } else if coverage >= 0.5 {
+        draw_pixel(canvas, x, y, color);
+    }
+}
+
+pub fn draw_circle(canvas: &mut Canvas, x_center: i32, y_center: i32, radius: i32, color: Color) {
     let mut x = radius;
     let mut y = 0;
     let mut p = 1 - radius; // The initial decision parameter
 
     // When radius is zero, only a single point will be printed at center
     if radius == 0 {
-        draw_pixel(frame, x_center, y_center, color);
+        draw_pixel(canvas, x_center, y_center, color);
         return;
     }
 
     if radius > 0 {
-        draw_pixel(frame, x_center + radius, y_center, color);
-        draw_pixel(frame, x_center - radius, y_center, color);
-        draw_pixel(frame, x_center, y_center + radius, color);
-        draw_pixel(frame, x_center, y_center - radius, color);
+        draw_pixel(canvas, x_center + radius, y_center, color);
+        draw_pixel(canvas, x_center - radius, y_center, color);
+        draw_pixel(canvas, x_center, y_center + radius, color);
+        draw_pixel(canvas, x_center, y_center - radius, color);
     }
 
     // Initial point on circle at the end of radius
-    draw_pixel(frame, x_center + x, y_center - y, color);
+    draw_pixel(canvas, x_center + x, y_center - y, color);
 
     while x > y {
         y += 1;
@@ -88,22 +373,22 @@ pub fn draw_circle(frame: &mut [u8], x_center: i32, y_center: i32, radius: i32,
         }
 
         // Symmetrical points in other octants
-        draw_pixel(frame, x_center + x, y_center - y, color);
-        draw_pixel(frame, x_center - x, y_center - y, color);
-        draw_pixel(frame, x_center + x, y_center + y, color);
-        draw_pixel(frame, x_center - x, y_center + y, color);
+        draw_pixel(canvas, x_center + x, y_center - y, color);
+        draw_pixel(canvas, x_center - x, y_center - y, color);
+        draw_pixel(canvas, x_center + x, y_center + y, color);
+        draw_pixel(canvas, x_center - x, y_center + y, color);
 
         if x != y {
-            draw_pixel(frame, x_center + y, y_center - x, color);
-            draw_pixel(frame, x_center - y, y_center - x, color);
-            draw_pixel(frame, x_center + y, y_center + x, color);
-            draw_pixel(frame, x_center - y, y_center + x, color);
+            draw_pixel(canvas, x_center + y, y_center - x, color);
+            draw_pixel(canvas, x_center - y, y_center - x, color);
+            draw_pixel(canvas, x_center + y, y_center + x, color);
+            draw_pixel(canvas, x_center - y, y_center + x, color);
         }
     }
 }
 
 pub fn draw_arcs(
-    frame: &mut [u8],
+    canvas: &mut Canvas,
     x_center: i32,
     y_center: i32,
     radius: i32,
@@ -116,19 +401,19 @@ pub fn draw_arcs(
 
     // When radius is zero, only a single point will be printed at center
     if radius == 0 {
-        draw_pixel(frame, x_center, y_center, color);
+        draw_pixel(canvas, x_center, y_center, color);
         return;
     }
 
     if radius > 0 {
-        draw_pixel(frame, x_center + radius, y_center, color);
-        draw_pixel(frame, x_center - radius, y_center, color);
-        draw_pixel(frame, x_center, y_center + radius, color);
-        draw_pixel(frame, x_center, y_center - radius, color);
+        draw_pixel(canvas, x_center + radius, y_center, color);
+        draw_pixel(canvas, x_center - radius, y_center, color);
+        draw_pixel(canvas, x_center, y_center + radius, color);
+        draw_pixel(canvas, x_center, y_center - radius, color);
     }
 
     // Initial point on circle at the end of radius
-    draw_pixel(frame, x_center + x, y_center - y, color);
+    draw_pixel(canvas, x_center + x, y_center - y, color);
 
     while x > y {
         y += 1;
@@ -146,16 +431,16 @@ pub fn draw_arcs(
         }
 
         // Symmetrical points in other octants
-        draw_pixel(frame, x_center + x, y_center - y, color);
-        draw_pixel(frame, x_center - x, y_center - y, color);
-        draw_pixel(frame, x_center + x, y_center + y, color);
-        draw_pixel(frame, x_center - x, y_center + y, color);
+        draw_pixel(canvas, x_center + x, y_center - y, color);
+        draw_pixel(canvas, x_center - x, y_center - y, color);
+        draw_pixel(canvas, x_center + x, y_center + y, color);
+        draw_pixel(canvas, x_center - x, y_center + y, color);
 
         if x != y {
-            draw_pixel(frame, x_center + y, y_center - x, color);
-            draw_pixel(frame, x_center - y, y_center - x, color);
-            draw_pixel(frame, x_center + y, y_center + x, color);
-            draw_pixel(frame, x_center - y, y_center + x, color);
+            draw_pixel(canvas, x_center + y, y_center - x, color);
+            draw_pixel(canvas, x_center - y, y_center - x, color);
+            draw_pixel(canvas, x_center + y, y_center + x, color);
+            draw_pixel(canvas, x_center - y, y_center + x, color);
         }
     }
 }
@@ -177,6 +462,14 @@ fn cast_and_rotate_point(
     (x_out as i32, y_out as i32)
 }
 
+// a point in a body's frame turned by its heading, the way body lines are
+// drawn by rotate_point. Offsets riding on a body go through here, so they
+// land where the body shows them
+pub fn rotate_body_offset(offset: Vec2, heading: f32) -> Vec2 {
+    let (x, y) = rotate_point(offset.x, offset.y, heading, 0., 0.);
+    Vec2::new(x, y)
+}
+
 pub fn rotate_point(x: f32, y: f32, rotation: f32, cx: f32, cy: f32) -> (f32, f32) {
     let x_translated = x - cx as f32;
     let y_translated = y - cy as f32;
@@ -1,6 +1,5 @@
-use crate::{
-    components::*, dev, gfx::draw::*, gfx::pixel::*, LOGICAL_WINDOW_HEIGHT, LOGICAL_WINDOW_WIDTH,
-};
+use crate::gfx::sprite::{blit_sprite, draw_missing_sprite, SpriteSheets};
+use crate::{components::*, dev, gfx::canvas::Canvas, gfx::draw::*, gfx::pixel::*};
 use nalgebra_glm::Vec2;
 
 // Body draw functions are an interface between floating point data coming in
@@ -12,18 +11,42 @@ use nalgebra_glm::Vec2;
 // functions) to integers (discrete and performant, primitive draw)
 
 // decides which body to draw
-pub fn draw_avatar(frame: &mut [u8], transform: &TransformCpt, drawbody: &DrawBodyCpt) {
+pub fn draw_avatar(
+    canvas: &mut Canvas,
+    transform: &TransformCpt,
+    drawbody: &DrawBodyCpt,
+    sprites: &SpriteSheets,
+) {
     match drawbody {
-        DrawBodyCpt { data, colorbody } => match data {
+        DrawBodyCpt {
+            data,
+            colorbody,
+            stroke,
+            fill,
+        } => match data {
             DrawData::Lines(x) => {
-                draw_body_of_lines(frame, transform, x.to_vec(), colorbody);
+                draw_body_of_lines(canvas, transform, x.to_vec(), colorbody, stroke, fill);
             }
             DrawData::R(r) => {
-                draw_body_of_circle(frame, transform, *r, colorbody);
+                draw_body_of_circle(canvas, transform, *r, colorbody, stroke, fill);
             }
             DrawData::Particle => {
-                draw_body_of_particle(frame, transform, colorbody);
+                draw_body_of_particle(canvas, transform, colorbody);
             }
+            DrawData::Sprite {
+                sheet,
+                frame: index,
+            } => match sprites.get(sheet) {
+                Some(sheet) => blit_sprite(
+                    canvas,
+                    sheet,
+                    *index,
+                    transform.position,
+                    transform.heading.get(),
+                    transform.scale,
+                ),
+                None => draw_missing_sprite(canvas, transform.position),
+            },
             _ => {}
         },
         _ => {}
@@ -65,47 +88,91 @@ fn transform_body_data(
 }
 
 pub fn draw_lines_by_list(
-    frame: &mut [u8],
+    canvas: &mut Canvas,
     vec: Vec<((f32, f32), (f32, f32))>,
     colorbody: &ColorBodyCpt,
+    stroke: &Stroke,
 ) {
     for (vec1, vec2) in vec {
-        draw_line(
-            frame,
-            vec1.0.round() as i32,
-            vec1.1.round() as i32,
-            vec2.0.round() as i32,
-            vec2.1.round() as i32,
-            colorbody.primary,
-        );
+        draw_stroked_line(canvas, vec1, vec2, colorbody.primary, stroke);
+    }
+}
+
+// picks the rasterizer for a stroke, hard 1px lines stay on the plain DDA
+pub fn draw_stroked_line(
+    canvas: &mut Canvas,
+    (x0, y0): (f32, f32),
+    (x1, y1): (f32, f32),
+    color: Color,
+    stroke: &Stroke,
+) {
+    match (stroke.width > 1., stroke.is_antialiased) {
+        (false, false) => draw_line(
+            canvas,
+            x0.round() as i32,
+            y0.round() as i32,
+            x1.round() as i32,
+            y1.round() as i32,
+            color,
+        ),
+        (false, true) => draw_line_aa(canvas, x0, y0, x1, y1, color),
+        (true, is_antialiased) => draw_thick_line(
+            canvas,
+            (x0, y0),
+            (x1, y1),
+            stroke.width,
+            color,
+            is_antialiased,
+        ),
     }
 }
 
 // DrawBodtCpt's DrawData::Lines
 pub fn draw_body_of_lines(
-    frame: &mut [u8],
+    canvas: &mut Canvas,
     transform: &TransformCpt,
     lines: Vec<(Vec2, Vec2)>,
     colorbody: &ColorBodyCpt,
+    stroke: &Stroke,
+    fill: &FillMode,
 ) {
     let transformed_line_endpoint_pairs = transform_body_data(transform, lines);
-    draw_lines_by_list(frame, transformed_line_endpoint_pairs, colorbody);
+    if *fill == FillMode::Filled {
+        fill_edges(
+            canvas,
+            &transformed_line_endpoint_pairs,
+            colorbody.secondary,
+        );
+    }
+    draw_lines_by_list(canvas, transformed_line_endpoint_pairs, colorbody, stroke);
 }
 
 // DrawBodtCpt's DrawData::R
 pub fn draw_body_of_circle(
-    frame: &mut [u8],
+    canvas: &mut Canvas,
     transform: &TransformCpt,
     r: f32,
     colorbody: &ColorBodyCpt,
+    stroke: &Stroke,
+    fill: &FillMode,
 ) {
-    draw_circle(
-        frame,
-        (transform.position.x) as i32,
-        (transform.position.y) as i32,
-        r as i32,
-        colorbody.primary,
-    );
+    let (x, y) = (transform.position.x, transform.position.y);
+    if *fill == FillMode::Filled {
+        fill_circle(canvas, x, y, r, colorbody.secondary);
+    }
+    match (stroke.width > 1., stroke.is_antialiased) {
+        (false, false) => draw_circle(canvas, x as i32, y as i32, r as i32, colorbody.primary),
+        (false, true) => draw_circle_aa(canvas, x, y, r, colorbody.primary),
+        (true, is_antialiased) => draw_thick_circle(
+            canvas,
+            x,
+            y,
+            r,
+            stroke.width,
+            colorbody.primary,
+            is_antialiased,
+        ),
+    }
 }
 
 pub fn generate_ship_lines() -> Vec<(Vec2, Vec2)> {
@@ -129,47 +196,136 @@ pub fn generate_ship_lines() -> Vec<(Vec2, Vec2)> {
     v
 }
 
-pub fn draw_body_of_particle(frame: &mut [u8], transform: &TransformCpt, colorbody: &ColorBodyCpt) {
+pub fn draw_body_of_particle(
+    canvas: &mut Canvas,
+    transform: &TransformCpt,
+    colorbody: &ColorBodyCpt,
+) {
     let x = transform.position.x;
     let y = transform.position.y;
-    draw_pixel(frame, x.round() as i32, y.round() as i32, colorbody.primary);
+    draw_pixel(
+        canvas,
+        x.round() as i32,
+        y.round() as i32,
+        colorbody.primary,
+    );
 }
 
-pub fn draw_body_of_orbiting_particle(
-    frame: &mut [u8],
-    transform: &TransformCpt,
-    drawbody: &DrawBodyCpt,
-    orbiting_particle: &OrbitParticleCpt,
-) {
-    let mut pos = Vec2::new(0., 0.);
-    pos.x += transform.position.x + orbiting_particle.angle.cos() * orbiting_particle.r;
-    pos.y += transform.position.y + orbiting_particle.angle.sin() * orbiting_particle.r;
-    draw_pixel(
-        frame,
-        (pos.x).round() as i32,
-        (pos.y).round() as i32,
-        drawbody.colorbody.primary,
+// hitscan beams, from emitter to hit point or max range
+pub fn draw_beam(canvas: &mut Canvas, start: Vec2, end: Vec2, color: Color) {
+    draw_line(
+        canvas,
+        start.x.round() as i32,
+        start.y.round() as i32,
+        end.x.round() as i32,
+        end.y.round() as i32,
+        color,
+    );
+    draw_glow(canvas, end.x, end.y, 6., color);
+}
+
+// translucent bubble just outside a shielded body, more opaque the stronger
+// the shield
+pub fn draw_shield_bubble(canvas: &mut Canvas, transform: &TransformCpt, r: f32, fraction: f32) {
+    if fraction <= 0. {
+        return;
+    }
+    let color = CYAN.with_alpha(0.15 + 0.45 * fraction.min(1.));
+    draw_circle_aa(
+        canvas,
+        transform.position.x,
+        transform.position.y,
+        r + 4.,
+        color,
     );
 }
 
-pub fn draw_boundary(frame: &mut [u8]) {
+// hp bar hovering above a body of radius r, with an optional shield bar on top.
+// fractions are of the full bar, fade scales all colors toward black
+pub fn draw_health_bar(
+    canvas: &mut Canvas,
+    position: Vec2,
+    r: f32,
+    hp_fraction: f32,
+    shield_fraction: Option<f32>,
+    fade: f32,
+) {
+    let width = (2. * r).round().max(8.) as i32;
+    let x = position.x.round() as i32 - width / 2;
+    let y = (position.y - r).round() as i32 - 5;
+
+    let hp_fraction = hp_fraction.clamp(0., 1.);
+    let hp_color = if hp_fraction > 0.5 {
+        GREEN
+    } else if hp_fraction > 0.25 {
+        YELLOW
+    } else {
+        RED
+    };
+    let background = GREY.faded(fade * 0.5);
+    for row in y..y + 2 {
+        draw_bar(
+            canvas,
+            x,
+            row,
+            width,
+            hp_fraction,
+            hp_color.faded(fade),
+            background,
+        );
+    }
+
+    if let Some(shield_fraction) = shield_fraction {
+        let shield_fraction = shield_fraction.clamp(0., 1.);
+        draw_bar(
+            canvas,
+            x,
+            y - 2,
+            width,
+            shield_fraction,
+            CYAN.faded(fade),
+            background,
+        );
+    }
+}
+
+// one pixel tall bar, filled left to right up to fraction of width
+fn draw_bar(
+    canvas: &mut Canvas,
+    x: i32,
+    y: i32,
+    width: i32,
+    fraction: f32,
+    color: Color,
+    background: Color,
+) {
+    let filled = (width as f32 * fraction).round() as i32;
+    if filled > 0 {
+        draw_line(canvas, x, y, x + filled - 1, y, color);
+    }
+    if filled < width {
+        draw_line(canvas, x + filled, y, x + width - 1, y, background);
+    }
+}
+
+pub fn draw_boundary(canvas: &mut Canvas) {
     let color = BLUE;
-    let width = LOGICAL_WINDOW_WIDTH as i32 - 1;
-    let height = LOGICAL_WINDOW_HEIGHT as i32 - 1;
-    draw_line(frame, 0, 0, width, 0, color);
-    draw_line(frame, width, 0, width, height, color);
-    draw_line(frame, width, height, 0, height, color);
-    draw_line(frame, 0, height, 0, 0, color);
+    let width = canvas.width() - 1;
+    let height = canvas.height() - 1;
+    draw_line(canvas, 0, 0, width, 0, color);
+    draw_line(canvas, width, 0, width, height, color);
+    draw_line(canvas, width, height, 0, height, color);
+    draw_line(canvas, 0, height, 0, 0, color);
 }
 
 pub fn draw_collision_rect(
-    frame: &mut [u8],
+    canvas: &mut Canvas,
     transform: &TransformCpt,
     collision_area: &BoxColliderCpt,
 ) {
     // ? cast or round then cast?
     draw_rect(
-        frame,
+        canvas,
         transform.position.x as i32,
         transform.position.y as i32,
         collision_area.w as i32,
@@ -179,13 +335,13 @@ pub fn draw_collision_rect(
 }
 
 pub fn draw_collision_circle(
-    frame: &mut [u8],
+    canvas: &mut Canvas,
     transform: &TransformCpt,
     collision_circle: &CircleColliderCpt,
 ) {
     // ? cast or round then cast?
     draw_circle(
-        frame,
+        canvas,
         transform.position.x as i32,
         transform.position.y as i32,
         collision_circle.r as i32,
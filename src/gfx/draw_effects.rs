@@ -0,0 +1,98 @@
+use std::f32::consts::TAU;
+
+use crate::components::{ColorBodyCpt, Easing, EffectCpt, EffectKind, TransformCpt};
+use crate::gfx::{
+    canvas::Canvas,
+    draw::{draw_arcs, draw_line_aa, draw_thick_circle, fill_circle},
+    text::{draw_stroke_text, stroke_text_size},
+};
+
+// Effect draw functions, the effect counterpart of draw_bodies. They turn an
+// effect's kind, size and progress (0 to 1 over its frames) into primitives.
+// New effects are a kind and an arm here, no system of their own
+
+// decides which effect to draw
+pub fn draw_effect(
+    canvas: &mut Canvas,
+    transform: &TransformCpt,
+    effect: &EffectCpt,
+    colorbody: &ColorBodyCpt,
+    frame: usize,
+    progress: f32,
+) {
+    let center = (transform.position.x, transform.position.y);
+    let r = effect.r;
+    let fade = 1. - progress;
+    match &effect.kind {
+        EffectKind::Ping { gap_factors } => {
+            // outer rings fade out
+            let frame_fade = 1. - frame as f32 / gap_factors.len() as f32;
+            draw_arcs(
+                canvas,
+                center.0 as i32,
+                center.1 as i32,
+                (r + r * (frame as f32 * 0.5)) as i32,
+                colorbody.primary.with_alpha(0.25 + 0.75 * frame_fade),
+                gap_factors[frame.min(gap_factors.len() - 1)],
+            );
+        }
+        EffectKind::ExplosionRing => {
+            // white hot core for the first third
+            let flash = 1. - progress * 3.;
+            if flash > 0. {
+                let core = colorbody.secondary.with_alpha(flash);
+                fill_circle(
+                    canvas,
+                    center.0,
+                    center.1,
+                    r * 0.4 * (1. - flash * 0.5),
+                    core,
+                );
+            }
+            let radius = r * Easing::CubicOut.ease(progress);
+            let color = colorbody.primary.with_alpha(fade);
+            draw_thick_circle(canvas, center.0, center.1, radius, 2., color, true);
+        }
+        EffectKind::SparkBurst { count } => {
+            let travel = r * Easing::QuadOut.ease(progress);
+            let color = colorbody.primary.with_alpha(fade);
+            for i in 0..*count {
+                // a fixed spread of angles and lengths, irregular enough to
+                // not read as a wheel
+                let angle = i as f32 * TAU / *count as f32 + (i * 7 % 5) as f32 * 0.1;
+                let length = r * 0.3 * fade * (0.6 + 0.2 * (i % 3) as f32);
+                let (sin, cos) = angle.sin_cos();
+                draw_line_aa(
+                    canvas,
+                    center.0 + cos * travel,
+                    center.1 + sin * travel,
+                    center.0 + cos * (travel + length),
+                    center.1 + sin * (travel + length),
+                    color,
+                );
+            }
+        }
+        EffectKind::Shockwave => {
+            let radius = r * Easing::QuadOut.ease(progress);
+            let width = 1. + 5. * fade;
+            let color = colorbody.primary.with_alpha(0.8 * fade);
+            draw_thick_circle(canvas, center.0, center.1, radius, width, color, true);
+        }
+        EffectKind::TextPop(text) => {
+            // overshoots to size quickly, then drifts up by r
+            let scale = 2. * Easing::BackOut.ease(progress * 4.);
+            if scale <= 0. {
+                return;
+            }
+            let (w, h) = stroke_text_size(text, scale);
+            draw_stroke_text(
+                canvas,
+                center.0 - w / 2.,
+                center.1 - h / 2. - r * progress,
+                text,
+                scale,
+                colorbody.primary.with_alpha(fade),
+            );
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Context};
+
+use crate::gfx::canvas::PixelBuffer;
+
+// PNG in and out of RGBA8 pixel data, for sprite sheets, screenshots and
+// golden images
+
+pub fn load_png(path: &Path) -> Result<PixelBuffer, anyhow::Error> {
+    let file = File::open(path)
+        .with_context(|| format!("Missing file: {:?}", path.to_str().unwrap_or("")))?;
+    let mut decoder = png::Decoder::new(BufReader::new(file));
+    // palettes and low bit depths expand, 16 bit channels strip to 8
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info()?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf)?;
+    buf.truncate(info.buffer_size());
+
+    let data: Vec<u8> = match info.color_type {
+        png::ColorType::Rgba => buf,
+        png::ColorType::Rgb => buf
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buf
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        png::ColorType::Grayscale => buf.iter().flat_map(|g| [*g, *g, *g, 255]).collect(),
+        png::ColorType::Indexed => return Err(anyhow!("Unexpanded palette in {:?}", path)),
+    };
+
+    Ok(PixelBuffer {
+        width: info.width,
+        height: info.height,
+        data,
+    })
+}
+
+// writes tightly packed RGBA8 rows, creating parent directories as needed
+pub fn save_png(path: &Path, width: u32, height: u32, rgba: &[u8]) -> Result<(), anyhow::Error> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let file = File::create(path)
+        .with_context(|| format!("Cannot create file: {:?}", path.to_str().unwrap_or("")))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&rgba[..width as usize * height as usize * 4])?;
+    Ok(())
+}
+
+// saves a frame under screenshots/ in the working directory, named by time
+pub fn save_screenshot(width: u32, height: u32, rgba: &[u8]) -> Result<PathBuf, anyhow::Error> {
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+    let path = PathBuf::from("screenshots").join(format!("aion_{millis}.png"));
+    save_png(&path, width, height, rgba)?;
+    Ok(path)
+}
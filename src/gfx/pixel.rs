@@ -1,3 +1,4 @@
+use crate::gfx::canvas::Canvas;
 use log::{error, warn};
 use rand::Rng;
 
@@ -88,6 +89,31 @@ impl Color {
             255 - self.0[3],
         ])
     }
+    // scales rgb toward black, for fading over the black background
+    pub fn faded(self, factor: f32) -> Color {
+        let f = factor.clamp(0., 1.);
+        Color([
+            (self.0[0] as f32 * f).round() as u8,
+            (self.0[1] as f32 * f).round() as u8,
+            (self.0[2] as f32 * f).round() as u8,
+            self.0[3],
+        ])
+    }
+    // t of the way from self to other, alpha included
+    pub fn lerp(self, other: Color, t: f32) -> Color {
+        let t = t.clamp(0., 1.);
+        Color([
+            lerp(self.0[0], other.0[0], t),
+            lerp(self.0[1], other.0[1], t),
+            lerp(self.0[2], other.0[2], t),
+            lerp(self.0[3], other.0[3], t),
+        ])
+    }
+    // same color with alpha scaled by factor, for translucent and fading draws
+    pub fn with_alpha(self, factor: f32) -> Color {
+        let a = (self.0[3] as f32 * factor.clamp(0., 1.)).round() as u8;
+        Color([self.0[0], self.0[1], self.0[2], a])
+    }
     pub fn rng() -> Color {
         let mut rng = rand::thread_rng();
         let z = rng.gen::<f32>();
@@ -154,6 +180,51 @@ impl Default for Color {
     }
 }
 
+// How a source color combines with the pixel already in the frame
+// - Replace: overwrites, alpha included (the original behaviour)
+// - AlphaOver: source over destination, weighted by source alpha
+// - Additive: adds the alpha weighted source, saturating. For glows and light
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum BlendMode {
+    Replace,
+    #[default]
+    AlphaOver,
+    Additive,
+}
+impl BlendMode {
+    // blends src into the 4 bytes of dst, coverage in [0, 1] scales the source
+    // weight for partially covered pixels
+    #[inline]
+    pub fn blend(self, dst: &mut [u8], src: Color, coverage: f32) {
+        let coverage = coverage.clamp(0., 1.);
+        let a = src.0[3] as f32 / 255. * coverage;
+        match self {
+            BlendMode::Replace if coverage >= 1. => dst.copy_from_slice(&src.0),
+            BlendMode::AlphaOver if a >= 1. => dst.copy_from_slice(&src.0),
+            BlendMode::Replace => {
+                for (d, s) in dst.iter_mut().zip(src.0) {
+                    *d = lerp(*d, s, coverage);
+                }
+            }
+            BlendMode::AlphaOver => {
+                for (d, s) in dst.iter_mut().zip([src.0[0], src.0[1], src.0[2], 255]) {
+                    *d = lerp(*d, s, a);
+                }
+            }
+            BlendMode::Additive => {
+                for (d, s) in dst.iter_mut().zip(src.0).take(3) {
+                    *d = d.saturating_add((s as f32 * a).round() as u8);
+                }
+            }
+        }
+    }
+}
+
+#[inline]
+fn lerp(from: u8, to: u8, t: f32) -> u8 {
+    (from as f32 + (to as f32 - from as f32) * t).round() as u8
+}
+
 pub const WHITE: Color = Color::new([255, 255, 255, 255]);
 pub const BLACK: Color = Color::new([0, 0, 0, 255]);
 pub const GRAY: Color = Color::new([128, 128, 128, 255]);
@@ -166,8 +237,6 @@ pub const MAGENTA: Color = Color::new([255, 0, 255, 255]); // DEBUG/TEST ONLY
 pub const CYAN: Color = Color::new([0, 255, 255, 255]);
 pub const GREY: Color = GRAY;
 
-pub fn clear(frame: &mut [u8]) {
-    for pixel in frame.chunks_exact_mut(4) {
-        pixel.copy_from_slice(BLACK.as_bytes());
-    }
+pub fn clear(canvas: &mut Canvas) {
+    canvas.fill(BLACK);
 }
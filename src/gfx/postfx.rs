@@ -0,0 +1,195 @@
+use crate::gfx::canvas::Canvas;
+use crate::util::time::Dt;
+
+// Retro post-processing of the rendered frame, CPU side
+// - phosphor: lit pixels fade out over time instead of vanishing on clear, like
+//   the persistence of a vector monitor. Each channel keeps the brighter of the
+//   new frame and the decayed previous one
+// - bloom: pixels above a brightness threshold are blurred at quarter
+//   resolution and added back on top, a cheap glow
+// - scanlines: every other row is darkened
+// - runs after the effects layer, HUD and debug drawing stay crisp and out of
+//   the phosphor history
+// - all stages are off by default, tunable from the debug window
+
+const BLOOM_DOWNSCALE: usize = 4;
+const BLOOM_BLUR_RADIUS: usize = 2;
+
+pub struct PostFx {
+    pub is_phosphor_on: bool,
+    pub phosphor_half_life: f32, // seconds for a lit pixel to fade to half
+    pub is_bloom_on: bool,
+    pub bloom_threshold: u8, // brightest channel needed to bloom
+    pub bloom_strength: f32,
+    pub is_scanlines_on: bool,
+    pub scanline_darkness: f32, // 0 leaves odd rows as they are, 1 blacks them out
+    history: Vec<u8>,
+    bloom: Vec<[f32; 3]>,
+    bloom_scratch: Vec<[f32; 3]>,
+}
+
+impl Default for PostFx {
+    fn default() -> Self {
+        PostFx {
+            is_phosphor_on: false,
+            phosphor_half_life: 0.05,
+            is_bloom_on: false,
+            bloom_threshold: 160,
+            bloom_strength: 0.8,
+            is_scanlines_on: false,
+            scanline_darkness: 0.35,
+            history: Vec::new(),
+            bloom: Vec::new(),
+            bloom_scratch: Vec::new(),
+        }
+    }
+}
+
+impl PostFx {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn apply(&mut self, canvas: &mut Canvas, rdt: &Dt) {
+        if self.is_phosphor_on {
+            self.phosphor(canvas, rdt);
+        } else {
+            self.history.clear();
+        }
+        if self.is_bloom_on {
+            self.bloom(canvas);
+        }
+        if self.is_scanlines_on {
+            self.scanlines(canvas);
+        }
+    }
+
+    fn phosphor(&mut self, canvas: &mut Canvas, rdt: &Dt) {
+        let row_len = canvas.width() as usize * 4;
+        let len = row_len * canvas.height() as usize;
+        if self.history.len() != len {
+            // first frame or the canvas changed size, nothing to persist
+            self.history = vec![0; len];
+        }
+        let decay = 0.5_f32.powf(rdt.secs() / self.phosphor_half_life.max(0.001));
+
+        for y in 0..canvas.height() {
+            let start = y as usize * row_len;
+            let history = &mut self.history[start..start + row_len];
+            for (px, old) in canvas
+                .row_mut(y)
+                .chunks_exact_mut(4)
+                .zip(history.chunks_exact_mut(4))
+            {
+                for (new, old) in px.iter_mut().zip(old.iter()).take(3) {
+                    *new = (*new).max((*old as f32 * decay) as u8);
+                }
+                old.copy_from_slice(px);
+            }
+        }
+    }
+
+    fn bloom(&mut self, canvas: &mut Canvas) {
+        let width = canvas.width() as usize;
+        let height = canvas.height() as usize;
+        let bw = width.div_ceil(BLOOM_DOWNSCALE);
+        let bh = height.div_ceil(BLOOM_DOWNSCALE);
+        self.bloom.clear();
+        self.bloom.resize(bw * bh, [0.; 3]);
+
+        // bright pass, summed into quarter resolution cells
+        let threshold = self.bloom_threshold;
+        for y in 0..height {
+            let row = canvas.row(y as i32);
+            let cells = &mut self.bloom[(y / BLOOM_DOWNSCALE) * bw..][..bw];
+            for (x, px) in row.chunks_exact(4).enumerate() {
+                if px[0].max(px[1]).max(px[2]) >= threshold {
+                    let cell = &mut cells[x / BLOOM_DOWNSCALE];
+                    for c in 0..3 {
+                        cell[c] += px[c] as f32;
+                    }
+                }
+            }
+        }
+        let cell_area = (BLOOM_DOWNSCALE * BLOOM_DOWNSCALE) as f32;
+        for cell in self.bloom.iter_mut() {
+            cell.iter_mut().for_each(|c| *c /= cell_area);
+        }
+
+        // separable box blur, horizontal into scratch then vertical back
+        self.bloom_scratch.clear();
+        self.bloom_scratch.resize(bw * bh, [0.; 3]);
+        box_blur(&self.bloom, &mut self.bloom_scratch, bh, bw, 1, bw);
+        box_blur(&self.bloom_scratch, &mut self.bloom, bw, bh, bw, 1);
+
+        // add back, bilinear between cell centers
+        let strength = self.bloom_strength;
+        for y in 0..height {
+            let fy = ((y as f32 + 0.5) / BLOOM_DOWNSCALE as f32 - 0.5).clamp(0., (bh - 1) as f32);
+            let (y0, ty) = (fy as usize, fy.fract());
+            let y1 = (y0 + 1).min(bh - 1);
+            let row = canvas.row_mut(y as i32);
+            for (x, px) in row.chunks_exact_mut(4).enumerate() {
+                let fx =
+                    ((x as f32 + 0.5) / BLOOM_DOWNSCALE as f32 - 0.5).clamp(0., (bw - 1) as f32);
+                let (x0, tx) = (fx as usize, fx.fract());
+                let x1 = (x0 + 1).min(bw - 1);
+                let cells = [
+                    self.bloom[y0 * bw + x0],
+                    self.bloom[y0 * bw + x1],
+                    self.bloom[y1 * bw + x0],
+                    self.bloom[y1 * bw + x1],
+                ];
+                for (c, channel) in px.iter_mut().take(3).enumerate() {
+                    let top = lerp(cells[0][c], cells[1][c], tx);
+                    let bottom = lerp(cells[2][c], cells[3][c], tx);
+                    let glow = lerp(top, bottom, ty) * strength;
+                    *channel = (*channel as f32 + glow).min(255.) as u8;
+                }
+            }
+        }
+    }
+
+    fn scanlines(&mut self, canvas: &mut Canvas) {
+        let keep = 1. - self.scanline_darkness.clamp(0., 1.);
+        for y in (1..canvas.height()).step_by(2) {
+            for px in canvas.row_mut(y).chunks_exact_mut(4) {
+                for c in px.iter_mut().take(3) {
+                    *c = (*c as f32 * keep) as u8;
+                }
+            }
+        }
+    }
+}
+
+// blurs `lines` runs of `len` cells, `step` apart within a run and `line_step`
+// apart between runs, so the same pass does rows or columns
+fn box_blur(
+    src: &[[f32; 3]],
+    dst: &mut [[f32; 3]],
+    lines: usize,
+    len: usize,
+    step: usize,
+    line_step: usize,
+) {
+    let taps = (2 * BLOOM_BLUR_RADIUS + 1) as f32;
+    for line in 0..lines {
+        let base = line * line_step;
+        for i in 0..len {
+            let mut sum = [0.; 3];
+            let from = i.saturating_sub(BLOOM_BLUR_RADIUS);
+            let to = (i + BLOOM_BLUR_RADIUS).min(len - 1);
+            for j in from..=to {
+                let cell = &src[base + j * step];
+                for c in 0..3 {
+                    sum[c] += cell[c];
+                }
+            }
+            dst[base + i * step] = sum.map(|s| s / taps);
+        }
+    }
+}
+
+fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
@@ -0,0 +1,200 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+    thread,
+    time::{self, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context;
+
+// Gameplay capture into animated GIFs
+// - while recording, rendered frames are sampled at a fixed capture rate,
+//   whatever the render fps, downscaled and kept in a ring buffer of
+//   duration x fps frames covering the last `duration` of play, older frames
+//   drop off
+// - saving encodes whatever the buffer holds on a background thread, so the
+//   game keeps running, and writes it under recordings/
+// - frames are timestamped, so the GIF plays back at the captured pace however
+//   the render rate varied
+// - memory is about (w / downscale) * (h / downscale) * 4 bytes per frame, a
+//   10s buffer at 20 fps is ~100MB for a 960x540 frame
+
+#[derive(Clone)]
+pub struct RecordedFrame {
+    pub time: time::Instant,
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+pub struct FrameRecorder {
+    pub is_recording: bool,
+    pub duration: time::Duration,
+    pub fps: u32, // captures per second
+    pub downscale: u32,
+    frames: VecDeque<RecordedFrame>,
+    next_capture: Option<time::Instant>,
+}
+
+impl Default for FrameRecorder {
+    fn default() -> Self {
+        FrameRecorder {
+            is_recording: false,
+            duration: time::Duration::new(10, 0),
+            fps: 20,
+            downscale: 2,
+            frames: VecDeque::new(),
+            next_capture: None,
+        }
+    }
+}
+
+impl FrameRecorder {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn toggle(&mut self) {
+        self.is_recording = !self.is_recording;
+        if !self.is_recording {
+            self.frames.clear();
+            self.next_capture = None;
+        }
+    }
+
+    // frames the buffer holds at most, duration at the capture rate
+    pub fn capacity(&self) -> usize {
+        (self.duration.as_secs_f32() * self.fps.max(1) as f32).ceil() as usize
+    }
+
+    pub fn buffered_frames(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn buffered_duration(&self) -> time::Duration {
+        match (self.frames.front(), self.frames.back()) {
+            (Some(first), Some(last)) => last.time - first.time,
+            _ => time::Duration::ZERO,
+        }
+    }
+
+    // offers a rendered frame of tightly packed RGBA8 rows
+    pub fn capture(&mut self, width: u32, height: u32, rgba: &[u8]) {
+        if !self.is_recording {
+            return;
+        }
+        let now = time::Instant::now();
+        if self.next_capture.is_some_and(|next| now < next) {
+            return;
+        }
+        // on schedule, unless rendering fell a whole interval behind
+        let interval = time::Duration::from_secs_f64(1. / self.fps.max(1) as f64);
+        self.next_capture = match self.next_capture {
+            Some(next) if now - next < interval => Some(next + interval),
+            _ => Some(now + interval),
+        };
+
+        while self
+            .frames
+            .front()
+            .is_some_and(|f| now - f.time > self.duration)
+        {
+            self.frames.pop_front();
+        }
+        let frame = downscaled(width, height, rgba, self.downscale.max(1), now);
+        // a GIF has one size, restart the buffer when the downscale changes
+        if self
+            .frames
+            .back()
+            .is_some_and(|f| (f.width, f.height) != (frame.width, frame.height))
+        {
+            self.frames.clear();
+        }
+        self.frames.push_back(frame);
+        while self.frames.len() > self.capacity() {
+            self.frames.pop_front();
+        }
+    }
+
+    // encodes a copy of the buffered frames off thread, recording carries on.
+    // Returns the path being written
+    pub fn save_gif(&mut self) -> Option<PathBuf> {
+        if self.frames.is_empty() {
+            return None;
+        }
+        let frames: Vec<RecordedFrame> = self.frames.iter().cloned().collect();
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis());
+        let path = PathBuf::from("recordings").join(format!("aion_{millis}.gif"));
+
+        let thread_path = path.clone();
+        thread::spawn(move || {
+            if let Err(e) = write_gif(&thread_path, frames) {
+                eprintln!("{e}");
+            }
+        });
+        Some(path)
+    }
+}
+
+// box filtered, each output pixel averages a factor x factor block
+fn downscaled(
+    width: u32,
+    height: u32,
+    rgba: &[u8],
+    factor: u32,
+    time: time::Instant,
+) -> RecordedFrame {
+    let out_width = width / factor;
+    let out_height = height / factor;
+    let mut out = Vec::with_capacity((out_width * out_height * 4) as usize);
+    let block = factor * factor;
+    for oy in 0..out_height {
+        for ox in 0..out_width {
+            let mut sum = [0u32; 4];
+            for y in oy * factor..(oy + 1) * factor {
+                for x in ox * factor..(ox + 1) * factor {
+                    let i = ((y * width + x) * 4) as usize;
+                    for (s, c) in sum.iter_mut().zip(&rgba[i..i + 4]) {
+                        *s += *c as u32;
+                    }
+                }
+            }
+            out.extend(sum.map(|s| (s / block) as u8));
+        }
+    }
+    RecordedFrame {
+        time,
+        width: out_width,
+        height: out_height,
+        rgba: out,
+    }
+}
+
+fn write_gif(path: &Path, frames: Vec<RecordedFrame>) -> Result<(), anyhow::Error> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let (width, height) = (frames[0].width as u16, frames[0].height as u16);
+    let file = File::create(path)
+        .with_context(|| format!("Cannot create file: {:?}", path.to_str().unwrap_or("")))?;
+    let mut encoder = gif::Encoder::new(BufWriter::new(file), width, height, &[])?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+
+    let times: Vec<time::Instant> = frames.iter().map(|f| f.time).collect();
+    for (i, mut recorded) in frames.into_iter().enumerate() {
+        // delay until the next frame, the last one holds as long as the one before
+        let delay = match (times.get(i + 1), i.checked_sub(1)) {
+            (Some(next), _) => *next - times[i],
+            (None, Some(prev)) => times[i] - times[prev],
+            (None, None) => time::Duration::ZERO,
+        };
+        let mut frame = gif::Frame::from_rgba_speed(width, height, &mut recorded.rgba, 10);
+        frame.delay = (delay.as_millis() / 10).max(1) as u16; // centiseconds
+        encoder.write_frame(&frame)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,186 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::anyhow;
+use nalgebra_glm::Vec2;
+
+use crate::gfx::{
+    canvas::Canvas,
+    draw::draw_pixel,
+    image::load_png,
+    pixel::{Color, MAGENTA},
+};
+
+// Sprite sheets loaded from PNG files in assets/
+// - a sheet is a grid of equally sized frames, indexed left to right, top to
+//   bottom. Single images are sheets of one frame
+// - sheets are registered under a SpriteNames, DrawData::Sprite refers to them
+//   by name and frame index
+// - pixels are kept as RGBA8 and blitted alpha-over, transparent pixels skip
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SpriteNames {
+    Beacon,
+}
+
+pub struct SpriteSheet {
+    pub frame_width: u32,
+    pub frame_height: u32,
+    pub frame_count: usize,
+    columns: u32,
+    sheet_width: u32,
+    rgba: Vec<u8>,
+}
+
+impl SpriteSheet {
+    pub fn from_png(
+        path: &Path,
+        frame_width: u32,
+        frame_height: u32,
+    ) -> Result<Self, anyhow::Error> {
+        let image = load_png(path)?;
+        if frame_width == 0
+            || frame_height == 0
+            || image.width % frame_width != 0
+            || image.height % frame_height != 0
+        {
+            return Err(anyhow!(
+                "{}x{} sheet {:?} does not divide into {}x{} frames",
+                image.width,
+                image.height,
+                path,
+                frame_width,
+                frame_height
+            ));
+        }
+        let columns = image.width / frame_width;
+        let rows = image.height / frame_height;
+
+        Ok(SpriteSheet {
+            frame_width,
+            frame_height,
+            frame_count: (columns * rows) as usize,
+            columns,
+            sheet_width: image.width,
+            rgba: image.data,
+        })
+    }
+
+    // color at x, y within a frame, frames past the end wrap around
+    pub fn pixel(&self, frame: usize, x: u32, y: u32) -> Color {
+        let frame = (frame % self.frame_count) as u32;
+        let sx = (frame % self.columns) * self.frame_width + x;
+        let sy = (frame / self.columns) * self.frame_height + y;
+        let i = ((sy * self.sheet_width + sx) * 4) as usize;
+        Color::RGBA(
+            self.rgba[i],
+            self.rgba[i + 1],
+            self.rgba[i + 2],
+            self.rgba[i + 3],
+        )
+    }
+}
+
+#[derive(Default)]
+pub struct SpriteSheets(HashMap<SpriteNames, SpriteSheet>);
+
+impl SpriteSheets {
+    pub fn new() -> Self {
+        Default::default()
+    }
+    pub fn get(&self, name: &SpriteNames) -> Option<&SpriteSheet> {
+        self.0.get(name)
+    }
+    pub fn insert(&mut self, name: SpriteNames, sheet: SpriteSheet) {
+        self.0.insert(name, sheet);
+    }
+    pub fn load_from_assets(
+        &mut self,
+        name: SpriteNames,
+        file_name: &str,
+        frame_width: u32,
+        frame_height: u32,
+    ) -> Result<(), anyhow::Error> {
+        let sheet = SpriteSheet::from_png(&assets_path(file_name)?, frame_width, frame_height)?;
+        self.insert(name, sheet);
+        Ok(())
+    }
+}
+
+pub fn load_essential_sprites(sheets: &mut SpriteSheets) -> Result<(), anyhow::Error> {
+    sheets.load_from_assets(SpriteNames::Beacon, "beacon.png", 16, 16)?;
+    Ok(())
+}
+
+// assets/ sits at the crate root in development and next to the binary in
+// release, same as the sound assets
+fn assets_path(file_name: &str) -> Result<PathBuf, anyhow::Error> {
+    let mut base_path = std::env::current_exe()?;
+    if cfg!(debug_assertions) {
+        (0..3).for_each(|_i| {
+            base_path.pop();
+        });
+    } else {
+        base_path.pop();
+    }
+    Ok(base_path.join("assets").join(file_name))
+}
+
+// blits a frame centered on center, rotated by rotation (radians, the frame's
+// +x facing heading) and scaled. Each covered frame pixel is mapped back into
+// the sprite and sampled nearest neighbour
+pub fn blit_sprite(
+    canvas: &mut Canvas,
+    sheet: &SpriteSheet,
+    index: usize,
+    center: Vec2,
+    rotation: f32,
+    scale: Vec2,
+) {
+    if scale.x == 0. || scale.y == 0. {
+        return;
+    }
+    let half_w = sheet.frame_width as f32 / 2.;
+    let half_h = sheet.frame_height as f32 / 2.;
+    let extent = (half_w * scale.x.abs())
+        .hypot(half_h * scale.y.abs())
+        .ceil() as i32;
+    let (sin, cos) = rotation.sin_cos();
+    let cx = center.x.round() as i32;
+    let cy = center.y.round() as i32;
+
+    for y in (cy - extent)..=(cy + extent) {
+        for x in (cx - extent)..=(cx + extent) {
+            // inverse rotate, then unscale, about the sprite center
+            let dx = x as f32 + 0.5 - center.x;
+            let dy = y as f32 + 0.5 - center.y;
+            let u = (dx * cos + dy * sin) / scale.x + half_w;
+            let v = (-dx * sin + dy * cos) / scale.y + half_h;
+            if u < 0. || v < 0. || u >= sheet.frame_width as f32 || v >= sheet.frame_height as f32 {
+                continue;
+            }
+            let color = sheet.pixel(index, u as u32, v as u32);
+            if color.as_bytes()[3] > 0 {
+                draw_pixel(canvas, x, y, color);
+            }
+        }
+    }
+}
+
+// stand-in for sheets that failed to load
+pub fn draw_missing_sprite(canvas: &mut Canvas, center: Vec2) {
+    for y in -4..=4 {
+        for x in -4..=4 {
+            if x == y || x == -y {
+                draw_pixel(
+                    canvas,
+                    center.x.round() as i32 + x,
+                    center.y.round() as i32 + y,
+                    MAGENTA,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use nalgebra_glm::Vec2;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::gfx::{
+    canvas::Canvas,
+    draw::draw_pixel_blended,
+    pixel::{BlendMode, Color, WHITE},
+};
+use crate::util::time::Dt;
+
+// Procedural parallax starfield behind the playfield
+// - stars come from a seed, the same seed gives the same sky
+// - layers further back scroll slower against the camera, the ship stands in
+//   for a camera until there is one
+// - positions wrap around the canvas, so the sky never runs out
+// - stars twinkle by a per star phase and speed. Density and color variety
+//   regenerate the sky when changed
+
+// (parallax factor, stars per 10000 px² at density 1, brightness, size px)
+const LAYERS: [(f32, f32, f32, i32); 3] = [
+    (0.02, 0.9, 0.35, 1),
+    (0.06, 0.4, 0.6, 1),
+    (0.15, 0.12, 0.9, 2),
+];
+const WARM: Color = Color::RGB(255, 226, 170);
+const COLD: Color = Color::RGB(170, 200, 255);
+
+struct Star {
+    layer: usize,
+    x: f32,
+    y: f32,
+    brightness: f32,
+    color: Color,
+    twinkle_phase: f32,
+    twinkle_speed: f32, // radians per second
+}
+
+pub struct Starfield {
+    pub is_on: bool,
+    pub seed: u64,
+    pub density: f32,
+    pub color_variety: f32, // 0 all white, 1 fully warm or cold tinted
+    pub twinkle: f32,       // how far a star dims at the low of its twinkle
+    stars: Vec<Star>,
+    generated_with: Option<(u64, f32, f32, i32, i32)>,
+    camera: Vec2,
+    time: f32,
+}
+
+impl Default for Starfield {
+    fn default() -> Self {
+        Starfield {
+            is_on: true,
+            seed: 0x5eed,
+            density: 1.,
+            color_variety: 0.5,
+            twinkle: 0.4,
+            stars: Vec::new(),
+            generated_with: None,
+            camera: Vec2::zeros(),
+            time: 0.,
+        }
+    }
+}
+
+impl Starfield {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    // camera is the world position the view follows, None keeps the last one
+    pub fn draw(&mut self, canvas: &mut Canvas, camera: Option<Vec2>, rdt: &Dt) {
+        if !self.is_on {
+            return;
+        }
+        self.time += rdt.secs();
+        if let Some(camera) = camera {
+            self.camera = camera;
+        }
+        let camera = self.camera;
+        let params = (
+            self.seed,
+            self.density,
+            self.color_variety,
+            canvas.width(),
+            canvas.height(),
+        );
+        if self.generated_with != Some(params) {
+            self.generate(canvas.width(), canvas.height());
+            self.generated_with = Some(params);
+        }
+
+        let w = canvas.width() as f32;
+        let h = canvas.height() as f32;
+        for star in &self.stars {
+            let (parallax, _, _, size) = LAYERS[star.layer];
+            let x = (star.x - camera.x * parallax).rem_euclid(w);
+            let y = (star.y - camera.y * parallax).rem_euclid(h);
+            let wave = 0.5 + 0.5 * (self.time * star.twinkle_speed + star.twinkle_phase).sin();
+            let color = star
+                .color
+                .faded(star.brightness * (1. - self.twinkle * wave));
+            for dy in 0..size {
+                for dx in 0..size {
+                    let (px, py) = (x as i32 + dx, y as i32 + dy);
+                    draw_pixel_blended(canvas, px, py, color, BlendMode::Additive);
+                }
+            }
+        }
+    }
+
+    fn generate(&mut self, width: i32, height: i32) {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let area = (width * height) as f32 / 10000.;
+        self.stars.clear();
+        for (layer, (_, per_area, brightness, _)) in LAYERS.iter().enumerate() {
+            let count = (area * per_area * self.density.max(0.)) as usize;
+            for _ in 0..count {
+                let tint = if rng.gen_bool(0.5) { WARM } else { COLD };
+                self.stars.push(Star {
+                    layer,
+                    x: rng.gen_range(0. ..width as f32),
+                    y: rng.gen_range(0. ..height as f32),
+                    brightness: brightness * rng.gen_range(0.6..=1.),
+                    color: WHITE.lerp(tint, self.color_variety * rng.gen::<f32>()),
+                    twinkle_phase: rng.gen_range(0. ..std::f32::consts::TAU),
+                    twinkle_speed: rng.gen_range(1. ..4.),
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use crate::gfx::{
+    canvas::Canvas,
+    draw::{draw_line, draw_pixel},
+    pixel::Color,
+};
+
+// Text drawn straight into the canvas, for HUD readouts and full screen messages
+// - bitmap: monospaced 5x7 font, crisp at integer scales
+// - stroke: vector font of line segments on a 4x6 grid, Asteroids style,
+//   scales to any size
+// - lowercase is drawn as uppercase, '\n' starts a new line and unknown
+//   characters advance the cursor without drawing
+
+pub const BITMAP_GLYPH_WIDTH: i32 = 5;
+pub const BITMAP_GLYPH_HEIGHT: i32 = 7;
+const BITMAP_GLYPH_SPACING: i32 = 1;
+const BITMAP_LINE_SPACING: i32 = 3;
+
+pub const STROKE_GLYPH_WIDTH: f32 = 4.;
+pub const STROKE_GLYPH_HEIGHT: f32 = 6.;
+const STROKE_GLYPH_SPACING: f32 = 2.;
+const STROKE_LINE_SPACING: f32 = 3.;
+
+////////////////////////////////////////////////////////////////////////////////
+// Bitmap Font
+////////////////////////////////////////////////////////////////////////////////
+
+#[rustfmt::skip]
+fn bitmap_glyph(c: char) -> Option<[u8; 7]> {
+    match c.to_ascii_uppercase() {
+        'A' => Some([0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001]),
+        'B' => Some([0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110]),
+        'C' => Some([0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110]),
+        'D' => Some([0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100]),
+        'E' => Some([0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111]),
+        'F' => Some([0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000]),
+        'G' => Some([0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111]),
+        'H' => Some([0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001]),
+        'I' => Some([0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110]),
+        'J' => Some([0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100]),
+        'K' => Some([0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001]),
+        'L' => Some([0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111]),
+        'M' => Some([0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001]),
+        'N' => Some([0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001]),
+        'O' => Some([0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110]),
+        'P' => Some([0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000]),
+        'Q' => Some([0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101]),
+        'R' => Some([0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001]),
+        'S' => Some([0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110]),
+        'T' => Some([0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100]),
+        'U' => Some([0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110]),
+        'V' => Some([0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100]),
+        'W' => Some([0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010]),
+        'X' => Some([0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001]),
+        'Y' => Some([0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100]),
+        'Z' => Some([0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111]),
+        '0' => Some([0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110]),
+        '1' => Some([0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110]),
+        '2' => Some([0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111]),
+        '3' => Some([0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110]),
+        '4' => Some([0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010]),
+        '5' => Some([0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110]),
+        '6' => Some([0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110]),
+        '7' => Some([0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000]),
+        '8' => Some([0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110]),
+        '9' => Some([0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100]),
+        '.' => Some([0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100]),
+        ',' => Some([0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b00100, 0b01000]),
+        ':' => Some([0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000]),
+        '!' => Some([0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000, 0b00100]),
+        '?' => Some([0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b00000, 0b00100]),
+        '-' => Some([0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000]),
+        '+' => Some([0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000]),
+        '=' => Some([0b00000, 0b00000, 0b11111, 0b00000, 0b11111, 0b00000, 0b00000]),
+        '/' => Some([0b00000, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b00000]),
+        '\'' => Some([0b01100, 0b00100, 0b01000, 0b00000, 0b00000, 0b00000, 0b00000]),
+        '"' => Some([0b01010, 0b01010, 0b01010, 0b00000, 0b00000, 0b00000, 0b00000]),
+        '(' => Some([0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010]),
+        ')' => Some([0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000]),
+        '%' => Some([0b11000, 0b11001, 0b00010, 0b00100, 0b01000, 0b10011, 0b00011]),
+        '*' => Some([0b00000, 0b00100, 0b10101, 0b01110, 0b10101, 0b00100, 0b00000]),
+        '_' => Some([0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b11111]),
+        '<' => Some([0b00010, 0b00100, 0b01000, 0b10000, 0b01000, 0b00100, 0b00010]),
+        '>' => Some([0b01000, 0b00100, 0b00010, 0b00001, 0b00010, 0b00100, 0b01000]),
+        '#' => Some([0b01010, 0b01010, 0b11111, 0b01010, 0b11111, 0b01010, 0b01010]),
+        _ => None,
+    }
+}
+
+// width and height in pixels of the widest line and all lines, at scale
+pub fn text_size(text: &str, scale: i32) -> (i32, i32) {
+    let advance = BITMAP_GLYPH_WIDTH + BITMAP_GLYPH_SPACING;
+    let widest = text.lines().map(|l| l.chars().count()).max().unwrap_or(0) as i32;
+    let n_lines = text.lines().count() as i32;
+    let width = (widest * advance - BITMAP_GLYPH_SPACING).max(0);
+    let height =
+        (n_lines * (BITMAP_GLYPH_HEIGHT + BITMAP_LINE_SPACING) - BITMAP_LINE_SPACING).max(0);
+    (width * scale, height * scale)
+}
+
+// draws text with its top-left corner at x, y, each glyph cell scale pixels wide
+pub fn draw_text(canvas: &mut Canvas, x: i32, y: i32, text: &str, scale: i32, color: Color) {
+    let mut cursor_x = x;
+    let mut cursor_y = y;
+    for c in text.chars() {
+        if c == '\n' {
+            cursor_x = x;
+            cursor_y += (BITMAP_GLYPH_HEIGHT + BITMAP_LINE_SPACING) * scale;
+            continue;
+        }
+        if let Some(rows) = bitmap_glyph(c) {
+            draw_glyph_cells(
+                canvas,
+                cursor_x,
+                cursor_y,
+                &rows,
+                BITMAP_GLYPH_WIDTH,
+                scale,
+                color,
+            );
+        }
+        cursor_x += (BITMAP_GLYPH_WIDTH + BITMAP_GLYPH_SPACING) * scale;
+    }
+}
+
+// plots one glyph, each row read from its low width bits left to right
+fn draw_glyph_cells(
+    canvas: &mut Canvas,
+    x: i32,
+    y: i32,
+    rows: &[u8],
+    width: i32,
+    scale: i32,
+    color: Color,
+) {
+    for (row, bits) in rows.iter().enumerate() {
+        for col in 0..width {
+            if bits & (1 << (width - 1 - col)) == 0 {
+                continue;
+            }
+            for sy in 0..scale {
+                for sx in 0..scale {
+                    draw_pixel(
+                        canvas,
+                        x + col * scale + sx,
+                        y + row as i32 * scale + sy,
+                        color,
+                    );
+                }
+            }
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Stroke Font
+////////////////////////////////////////////////////////////////////////////////
+
+// segments as (x0, y0, x1, y1) on the glyph grid, y down
+#[rustfmt::skip]
+fn stroke_glyph(c: char) -> &'static [(u8, u8, u8, u8)] {
+    match c.to_ascii_uppercase() {
+        'A' => &[(0, 6, 2, 0), (2, 0, 4, 6), (1, 3, 3, 3)],
+        'B' => &[(0, 0, 0, 6), (0, 0, 3, 0), (3, 0, 4, 1), (4, 1, 4, 2), (4, 2, 3, 3), (0, 3, 3, 3), (3, 3, 4, 4), (4, 4, 4, 5), (4, 5, 3, 6), (3, 6, 0, 6)],
+        'C' => &[(4, 0, 0, 0), (0, 0, 0, 6), (0, 6, 4, 6)],
+        'D' => &[(0, 0, 0, 6), (0, 0, 2, 0), (2, 0, 4, 2), (4, 2, 4, 4), (4, 4, 2, 6), (2, 6, 0, 6)],
+        'E' => &[(4, 0, 0, 0), (0, 0, 0, 6), (0, 6, 4, 6), (0, 3, 3, 3)],
+        'F' => &[(4, 0, 0, 0), (0, 0, 0, 6), (0, 3, 3, 3)],
+        'G' => &[(4, 1, 4, 0), (4, 0, 0, 0), (0, 0, 0, 6), (0, 6, 4, 6), (4, 6, 4, 3), (4, 3, 2, 3)],
+        'H' => &[(0, 0, 0, 6), (4, 0, 4, 6), (0, 3, 4, 3)],
+        'I' => &[(0, 0, 4, 0), (2, 0, 2, 6), (0, 6, 4, 6)],
+        'J' => &[(4, 0, 4, 6), (4, 6, 2, 6), (2, 6, 0, 4)],
+        'K' => &[(0, 0, 0, 6), (4, 0, 0, 3), (0, 3, 4, 6)],
+        'L' => &[(0, 0, 0, 6), (0, 6, 4, 6)],
+        'M' => &[(0, 6, 0, 0), (0, 0, 2, 2), (2, 2, 4, 0), (4, 0, 4, 6)],
+        'N' => &[(0, 6, 0, 0), (0, 0, 4, 6), (4, 6, 4, 0)],
+        'O' => &[(0, 0, 4, 0), (4, 0, 4, 6), (4, 6, 0, 6), (0, 6, 0, 0)],
+        'P' => &[(0, 6, 0, 0), (0, 0, 4, 0), (4, 0, 4, 3), (4, 3, 0, 3)],
+        'Q' => &[(0, 0, 4, 0), (4, 0, 4, 4), (4, 4, 2, 6), (2, 6, 0, 6), (0, 6, 0, 0), (2, 4, 4, 6)],
+        'R' => &[(0, 6, 0, 0), (0, 0, 4, 0), (4, 0, 4, 3), (4, 3, 0, 3), (1, 3, 4, 6)],
+        'S' => &[(4, 0, 0, 0), (0, 0, 0, 3), (0, 3, 4, 3), (4, 3, 4, 6), (4, 6, 0, 6)],
+        'T' => &[(0, 0, 4, 0), (2, 0, 2, 6)],
+        'U' => &[(0, 0, 0, 6), (0, 6, 4, 6), (4, 6, 4, 0)],
+        'V' => &[(0, 0, 2, 6), (2, 6, 4, 0)],
+        'W' => &[(0, 0, 0, 6), (0, 6, 2, 4), (2, 4, 4, 6), (4, 6, 4, 0)],
+        'X' => &[(0, 0, 4, 6), (4, 0, 0, 6)],
+        'Y' => &[(0, 0, 2, 3), (4, 0, 2, 3), (2, 3, 2, 6)],
+        'Z' => &[(0, 0, 4, 0), (4, 0, 0, 6), (0, 6, 4, 6)],
+        '0' => &[(0, 0, 4, 0), (4, 0, 4, 6), (4, 6, 0, 6), (0, 6, 0, 0), (4, 0, 0, 6)],
+        '1' => &[(2, 0, 2, 6)],
+        '2' => &[(0, 0, 4, 0), (4, 0, 4, 3), (4, 3, 0, 3), (0, 3, 0, 6), (0, 6, 4, 6)],
+        '3' => &[(0, 0, 4, 0), (4, 0, 4, 6), (4, 6, 0, 6), (0, 3, 4, 3)],
+        '4' => &[(0, 0, 0, 3), (0, 3, 4, 3), (4, 0, 4, 6)],
+        '5' => &[(4, 0, 0, 0), (0, 0, 0, 3), (0, 3, 4, 3), (4, 3, 4, 6), (4, 6, 0, 6)],
+        '6' => &[(0, 0, 0, 6), (0, 6, 4, 6), (4, 6, 4, 3), (4, 3, 0, 3)],
+        '7' => &[(0, 0, 4, 0), (4, 0, 4, 6)],
+        '8' => &[(0, 0, 4, 0), (4, 0, 4, 6), (4, 6, 0, 6), (0, 6, 0, 0), (0, 3, 4, 3)],
+        '9' => &[(4, 3, 0, 3), (0, 3, 0, 0), (0, 0, 4, 0), (4, 0, 4, 6)],
+        '.' => &[(2, 5, 2, 6)],
+        ',' => &[(2, 5, 1, 6)],
+        ':' => &[(2, 1, 2, 2), (2, 4, 2, 5)],
+        '!' => &[(2, 0, 2, 4), (2, 5, 2, 6)],
+        '?' => &[(0, 0, 4, 0), (4, 0, 4, 3), (4, 3, 2, 3), (2, 3, 2, 4), (2, 5, 2, 6)],
+        '-' => &[(1, 3, 3, 3)],
+        '+' => &[(1, 3, 3, 3), (2, 2, 2, 4)],
+        '=' => &[(1, 2, 3, 2), (1, 4, 3, 4)],
+        '/' => &[(0, 6, 4, 0)],
+        '\'' => &[(2, 0, 2, 1)],
+        '(' => &[(3, 0, 1, 2), (1, 2, 1, 4), (1, 4, 3, 6)],
+        ')' => &[(1, 0, 3, 2), (3, 2, 3, 4), (3, 4, 1, 6)],
+        '*' => &[(0, 1, 4, 5), (4, 1, 0, 5), (2, 1, 2, 5)],
+        '_' => &[(0, 6, 4, 6)],
+        '<' => &[(3, 1, 1, 3), (1, 3, 3, 5)],
+        '>' => &[(1, 1, 3, 3), (3, 3, 1, 5)],
+        _ => &[],
+    }
+}
+
+// width and height in pixels of the widest line and all lines, at scale
+pub fn stroke_text_size(text: &str, scale: f32) -> (f32, f32) {
+    let advance = STROKE_GLYPH_WIDTH + STROKE_GLYPH_SPACING;
+    let widest = text.lines().map(|l| l.chars().count()).max().unwrap_or(0) as f32;
+    let n_lines = text.lines().count() as f32;
+    let width = (widest * advance - STROKE_GLYPH_SPACING).max(0.);
+    let height =
+        (n_lines * (STROKE_GLYPH_HEIGHT + STROKE_LINE_SPACING) - STROKE_LINE_SPACING).max(0.);
+    (width * scale, height * scale)
+}
+
+// draws text with its top-left corner at x, y, one grid unit is scale pixels
+pub fn draw_stroke_text(canvas: &mut Canvas, x: f32, y: f32, text: &str, scale: f32, color: Color) {
+    let mut cursor_x = x;
+    let mut cursor_y = y;
+    for c in text.chars() {
+        if c == '\n' {
+            cursor_x = x;
+            cursor_y += (STROKE_GLYPH_HEIGHT + STROKE_LINE_SPACING) * scale;
+            continue;
+        }
+        for (x0, y0, x1, y1) in stroke_glyph(c) {
+            draw_line(
+                canvas,
+                (cursor_x + *x0 as f32 * scale).round() as i32,
+                (cursor_y + *y0 as f32 * scale).round() as i32,
+                (cursor_x + *x1 as f32 * scale).round() as i32,
+                (cursor_y + *y1 as f32 * scale).round() as i32,
+                color,
+            );
+        }
+        cursor_x += (STROKE_GLYPH_WIDTH + STROKE_GLYPH_SPACING) * scale;
+    }
+}
@@ -0,0 +1,216 @@
+// Golden image regression tests for the renderer
+// - each case draws a scripted scene off screen, no window or GPU, and compares
+//   it against tests/golden/<name>.png within a per channel tolerance
+// - set UPDATE_GOLDEN=1 to write new goldens and accept intentional visual
+//   changes, a missing golden fails the test otherwise
+// - on mismatch the actual frame is written to target/golden/<name>.png
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use nalgebra_glm::Vec2;
+
+use crate::{
+    archetypes::gen_effect,
+    avatars::{Beacon, Circloid, HumanShip, MineLayer},
+    components::{EffectKind, TransformCpt},
+    game::Game,
+    gfx::{
+        canvas::PixelBuffer,
+        draw::*,
+        image::{load_png, save_png},
+        pixel::*,
+        sprite::{SpriteNames, SpriteSheet},
+        text::{draw_stroke_text, draw_text},
+    },
+    scenario::spawn_scenario_shootingallery,
+    util::time::{advance_frozen_clock, freeze_clock, Dt},
+    DebugContext, LOGICAL_WINDOW_HEIGHT, LOGICAL_WINDOW_WIDTH,
+};
+
+const CHANNEL_TOLERANCE: u8 = 2;
+const MAX_MISMATCHED_FRACTION: f32 = 0.001;
+
+fn manifest_path(relative: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(relative)
+}
+
+fn assert_matches_golden(name: &str, actual: &PixelBuffer) {
+    let golden_path = manifest_path("tests/golden").join(format!("{name}.png"));
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        save_png(&golden_path, actual.width, actual.height, &actual.data).unwrap();
+        eprintln!("{name}: wrote golden image {golden_path:?}");
+        return;
+    }
+    assert!(
+        golden_path.exists(),
+        "{name}: no golden image at {golden_path:?}, run with UPDATE_GOLDEN=1 to write it"
+    );
+
+    let expected = load_png(&golden_path).unwrap();
+    assert_eq!(
+        (actual.width, actual.height),
+        (expected.width, expected.height),
+        "{name}: size differs from golden"
+    );
+    let mismatched = actual
+        .data
+        .chunks_exact(4)
+        .zip(expected.data.chunks_exact(4))
+        .filter(|(a, e)| {
+            a.iter()
+                .zip(e.iter())
+                .any(|(a, e)| a.abs_diff(*e) > CHANNEL_TOLERANCE)
+        })
+        .count();
+    let fraction = mismatched as f32 / (actual.width * actual.height) as f32;
+    if fraction > MAX_MISMATCHED_FRACTION {
+        let actual_path = manifest_path("target/golden").join(format!("{name}.png"));
+        save_png(&actual_path, actual.width, actual.height, &actual.data).unwrap();
+        panic!(
+            "{name}: {mismatched} pixels ({:.2}%) differ from {golden_path:?}, actual frame written to {actual_path:?}",
+            fraction * 100.
+        );
+    }
+}
+
+// timers and cooldowns only move with the Dt each update is given, so scenes
+// come out the same however fast the test runs
+fn headless_game() -> Game {
+    freeze_clock();
+    let mut game = Game::headless();
+    let beacon = SpriteSheet::from_png(&manifest_path("assets/beacon.png"), 16, 16).unwrap();
+    game.sprites.insert(SpriteNames::Beacon, beacon);
+    game
+}
+
+fn render_game(game: &mut Game, dbg_ctx: &DebugContext) -> PixelBuffer {
+    let mut buffer = PixelBuffer::new(LOGICAL_WINDOW_WIDTH as u32, LOGICAL_WINDOW_HEIGHT as u32);
+    game.render_to(&mut buffer.canvas(), dbg_ctx, Dt(Duration::ZERO), 1.);
+    buffer
+}
+
+#[test]
+fn golden_primitives() {
+    let mut buffer = PixelBuffer::new(160, 120);
+    let mut canvas = buffer.canvas();
+    clear(&mut canvas);
+
+    draw_line(&mut canvas, 5, 5, 50, 30, WHITE);
+    draw_line_aa(&mut canvas, 5., 15., 50., 40., WHITE);
+    draw_thick_line(&mut canvas, (5., 30.), (50., 55.), 4., ORANGE, true);
+    draw_rect(&mut canvas, 60, 5, 30, 20, BLUE);
+    fill_rect(&mut canvas, 65, 10, 20, 10, GREEN);
+    draw_circle(&mut canvas, 120, 20, 15, YELLOW);
+    draw_circle_aa(&mut canvas, 120., 20., 10.5, CYAN);
+    fill_circle(&mut canvas, 30., 85., 18., RED);
+    draw_thick_circle(&mut canvas, 30., 85., 18., 3., WHITE, false);
+    draw_arcs(&mut canvas, 120, 60, 15, ORANGE, 2);
+
+    // concave arrow head, even-odd filled
+    fill_polygon(
+        &mut canvas,
+        &[(60., 40.), (100., 55.), (60., 70.), (72., 55.)],
+        GREEN,
+    );
+    // translucent and additive over what is already drawn
+    fill_rect(&mut canvas, 20, 70, 30, 30, BLUE.with_alpha(0.5));
+    draw_glow(&mut canvas, 120., 95., 15., RED);
+
+    draw_text(&mut canvas, 60, 80, "AION 42", 1, WHITE);
+    draw_stroke_text(&mut canvas, 60., 95., "HUD", 2., YELLOW);
+
+    assert_matches_golden("primitives", &buffer);
+}
+
+#[test]
+fn golden_bodies() {
+    let mut game = headless_game();
+    let mut ship = HumanShip::new();
+    ship.0.heading.set(0.5);
+    game.world.spawn(ship);
+    game.world.spawn(Circloid::new());
+    game.world.spawn(MineLayer::new());
+    game.world.spawn(Beacon::new(200., 100.));
+
+    let dbg_ctx = DebugContext {
+        is_drawing_collisionareas: true,
+        ..DebugContext::new()
+    };
+    assert_matches_golden("bodies", &render_game(&mut game, &dbg_ctx));
+}
+
+#[test]
+fn golden_effects() {
+    let mut game = headless_game();
+    game.world.spawn(HumanShip::new());
+    let effects = [
+        (
+            EffectKind::Ping {
+                gap_factors: [1, 2, 3, 6],
+            },
+            6.,
+        ),
+        (EffectKind::ExplosionRing, 40.),
+        (EffectKind::SparkBurst { count: 12 }, 50.),
+        (EffectKind::Shockwave, 80.),
+        (EffectKind::TextPop("POW".to_string()), 30.),
+    ];
+    for (i, (kind, r)) in effects.into_iter().enumerate() {
+        let mut effect = gen_effect(kind, 100. + i as f32 * 180., 270., r, ORANGE);
+        // a third of the way through
+        effect.2.current_frame = effect.2.frame_count / 3;
+        game.world.spawn(effect);
+    }
+    assert_matches_golden("effects", &render_game(&mut game, &DebugContext::new()));
+}
+
+#[test]
+fn golden_game_over() {
+    let mut game = headless_game();
+    assert_matches_golden("game_over", &render_game(&mut game, &DebugContext::new()));
+}
+
+#[test]
+fn golden_postfx() {
+    let mut game = headless_game();
+    game.world.spawn(HumanShip::new());
+    game.world.spawn(Circloid::new());
+    game.postfx.is_phosphor_on = true;
+    game.postfx.is_bloom_on = true;
+    game.postfx.is_scanlines_on = true;
+
+    // the ship moves between frames, the first position lingers as a trail
+    let dbg_ctx = DebugContext::new();
+    render_game(&mut game, &dbg_ctx);
+    for (_id, transform) in game.world.query_mut::<&mut TransformCpt>() {
+        transform.position += Vec2::new(40., 0.);
+    }
+    let mut buffer = PixelBuffer::new(LOGICAL_WINDOW_WIDTH as u32, LOGICAL_WINDOW_HEIGHT as u32);
+    game.render_to(
+        &mut buffer.canvas(),
+        &dbg_ctx,
+        Dt(Duration::from_secs_f64(1. / 60.)),
+        1.,
+    );
+    assert_matches_golden("postfx", &buffer);
+}
+
+#[test]
+fn golden_shootingallery_after_half_a_second() {
+    let mut game = headless_game();
+    game.world.spawn(HumanShip::new());
+    spawn_scenario_shootingallery(&mut game.world);
+    game.loop_controller.run();
+    let dt = Duration::from_secs_f64(1. / 60.);
+    for _ in 0..30 {
+        advance_frozen_clock(dt);
+        game.update(Dt(dt));
+    }
+    assert_matches_golden(
+        "shootingallery_30_updates",
+        &render_game(&mut game, &DebugContext::new()),
+    );
+}
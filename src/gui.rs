@@ -9,13 +9,18 @@ use pixels::{wgpu, PixelsContext};
 use winit::event_loop::EventLoopWindowTarget;
 use winit::window::Window;
 
+use hecs::With;
+use rand::Rng;
+
 use crate::{
     archetypes::{
         gen_buncha_rng_circloids, gen_buncha_rng_particles, gen_buncha_rng_projectiles,
-        gen_circloids,
+        gen_circloids, gen_effect, gen_orbital_shield,
     },
+    components::{EffectKind, HumanInputCpt, ProjectileEmitterCpt, TransformCpt},
     dev,
     game::{Game, GetRunState, RunState},
+    gfx::pixel::{GREEN, ORANGE},
     DebugContext, LOGICAL_WINDOW_HEIGHT, LOGICAL_WINDOW_WIDTH, PHYSICAL_WINDOW_HEIGHT,
     PHYSICAL_WINDOW_WIDTH,
 };
@@ -50,6 +55,7 @@ struct Gui {
     n_spawn_circloids: i32,
     n_spawn_particles: i32,
     n_spawn_projectiles: i32,
+    n_spawn_orbiters: usize,
 }
 
 impl Framework {
@@ -177,6 +183,7 @@ impl Gui {
             n_spawn_particles: 1,
             n_spawn_circloids: 1,
             n_spawn_projectiles: 1,
+            n_spawn_orbiters: 6,
         }
     }
 
@@ -231,6 +238,15 @@ impl Gui {
                         gs.update_fps, gs.update_frame_count
                     ));
                     ui.label(format!(" n_ents: {}", gs.game.world.len()));
+                    if let Some((_id, emitter)) = gs
+                        .game
+                        .world
+                        .query_mut::<With<&ProjectileEmitterCpt, &HumanInputCpt>>()
+                        .into_iter()
+                        .next()
+                    {
+                        ui.label(format!(" weapon: {}", emitter.weapon.spec().name));
+                    }
                 })
             });
         });
@@ -245,6 +261,88 @@ impl Gui {
                         &mut gs.dbg_ctx.is_drawing_collisionareas,
                         "show collision areas",
                     );
+                    ui.checkbox(
+                        &mut gs.dbg_ctx.is_drawing_damage_overlay,
+                        "show health bars and damage numbers",
+                    );
+                    if ui.button("screenshot").clicked() {
+                        gs.dbg_ctx.is_screenshot_requested = true;
+                    }
+
+                    let starfield = &mut gs.game.starfield;
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut starfield.is_on, "stars");
+                        ui.add(egui::DragValue::new(&mut starfield.seed).prefix("seed "));
+                        ui.add(
+                            egui::Slider::new(&mut starfield.density, 0.0..=4.0).text("density"),
+                        );
+                    });
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::Slider::new(&mut starfield.color_variety, 0.0..=1.0)
+                                .text("color variety"),
+                        );
+                        ui.add(
+                            egui::Slider::new(&mut starfield.twinkle, 0.0..=1.0).text("twinkle"),
+                        );
+                    });
+
+                    let postfx = &mut gs.game.postfx;
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut postfx.is_phosphor_on, "phosphor");
+                        ui.add(
+                            egui::Slider::new(&mut postfx.phosphor_half_life, 0.01..=0.5)
+                                .text("half life s"),
+                        );
+                    });
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut postfx.is_bloom_on, "bloom");
+                        ui.add(
+                            egui::Slider::new(&mut postfx.bloom_threshold, 0..=255)
+                                .text("threshold"),
+                        );
+                        ui.add(
+                            egui::Slider::new(&mut postfx.bloom_strength, 0.0..=3.0)
+                                .text("strength"),
+                        );
+                    });
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut postfx.is_scanlines_on, "scanlines");
+                        ui.add(
+                            egui::Slider::new(&mut postfx.scanline_darkness, 0.0..=1.0)
+                                .text("darkness"),
+                        );
+                    });
+
+                    let recorder = &mut gs.game.recorder;
+                    ui.horizontal(|ui| {
+                        let mut is_recording = recorder.is_recording;
+                        if ui.checkbox(&mut is_recording, "record gif").changed() {
+                            recorder.toggle();
+                        }
+                        ui.label(format!(
+                            "{} frames, {:.1}s",
+                            recorder.buffered_frames(),
+                            recorder.buffered_duration().as_secs_f32()
+                        ));
+                    });
+                    let mut duration_secs = recorder.duration.as_secs_f32();
+                    if ui
+                        .add(egui::Slider::new(&mut duration_secs, 1.0..=30.0).text("seconds kept"))
+                        .changed()
+                    {
+                        recorder.duration = std::time::Duration::from_secs_f32(duration_secs);
+                    }
+                    ui.add(egui::Slider::new(&mut recorder.fps, 5..=50).text("capture fps"));
+                    ui.add(egui::Slider::new(&mut recorder.downscale, 1..=4).text("downscale"));
+                    if ui
+                        .button(format!("save last {:.0} seconds", duration_secs))
+                        .clicked()
+                    {
+                        if let Some(path) = recorder.save_gif() {
+                            dev!("Saving recording to {:?}", path);
+                        }
+                    }
 
                     ui.horizontal(|ui| {
                         if ui.button("spawn particles").clicked() {
@@ -273,6 +371,59 @@ impl Gui {
                         );
                     });
 
+                    ui.horizontal(|ui| {
+                        ui.label("spawn effect");
+                        let effects = [
+                            (
+                                "ping",
+                                EffectKind::Ping {
+                                    gap_factors: [1, 2, 3, 6],
+                                },
+                                6.,
+                            ),
+                            ("explosion", EffectKind::ExplosionRing, 40.),
+                            ("sparks", EffectKind::SparkBurst { count: 12 }, 50.),
+                            ("shockwave", EffectKind::Shockwave, 80.),
+                            ("text", EffectKind::TextPop("POW".to_string()), 30.),
+                        ];
+                        for (name, kind, r) in effects {
+                            if ui.button(name).clicked() {
+                                let mut rng = rand::thread_rng();
+                                gs.game.world.spawn(gen_effect(
+                                    kind,
+                                    rng.gen::<f32>() * LOGICAL_WINDOW_WIDTH,
+                                    rng.gen::<f32>() * LOGICAL_WINDOW_HEIGHT,
+                                    r,
+                                    ORANGE,
+                                ));
+                            }
+                        }
+                    });
+
+                    ui.horizontal(|ui| {
+                        if ui.button("spawn orbital shield").clicked() {
+                            let player = gs
+                                .game
+                                .world
+                                .query_mut::<With<&TransformCpt, &HumanInputCpt>>()
+                                .into_iter()
+                                .next()
+                                .map(|(ent, transform)| (ent, transform.position));
+                            if let Some((ship, position)) = player {
+                                gs.game.world.extend(gen_orbital_shield(
+                                    ship,
+                                    position,
+                                    self.n_spawn_orbiters,
+                                    35.,
+                                    200.,
+                                    5,
+                                    GREEN,
+                                ));
+                            }
+                        }
+                        ui.add(egui::Slider::new(&mut self.n_spawn_orbiters, 1..=12).step_by(1.));
+                    });
+
                     if ui.button("step update").clicked() {
                         dev!("step update");
                     }
@@ -1,13 +1,18 @@
 #![allow(unused)]
+mod abilities;
 mod archetypes;
 mod audio;
 mod avatars;
 mod components;
 mod game;
+#[cfg(test)]
+mod golden_tests;
 mod gui;
 mod init;
+mod raycast;
 mod scenario;
 mod systems;
+mod weapons;
 
 pub mod gfx;
 pub mod util;
@@ -22,6 +27,7 @@ use std::{
     time::{Duration, Instant},
 };
 
+use gfx::image::save_screenshot;
 use gfx::pixel::{Color, BLACK};
 use gui::Framework;
 use pixels::{Error, Pixels, SurfaceTexture};
@@ -65,16 +71,37 @@ fn process_dbg_keys(game: &mut Game, dbg_ctx: &mut DebugContext) {
     if game.input.key_pressed(VirtualKeyCode::Key1) {
         dbg_ctx.is_drawing_collisionareas = !dbg_ctx.is_drawing_collisionareas;
     }
+    if game.input.key_pressed(VirtualKeyCode::Key2) {
+        dbg_ctx.is_drawing_damage_overlay = !dbg_ctx.is_drawing_damage_overlay;
+    }
+    if game.input.key_pressed(VirtualKeyCode::F12) {
+        dbg_ctx.is_screenshot_requested = true;
+    }
+    if game.input.key_pressed(VirtualKeyCode::F10) {
+        game.recorder.toggle();
+    }
+    if game.input.key_pressed(VirtualKeyCode::F11) {
+        save_recording(game);
+    }
 
     if game.input.key_pressed(VirtualKeyCode::R) {
         game.restart();
     }
 }
 
+// writes the recorder's buffer as a GIF, encoding finishes in the background
+fn save_recording(game: &mut Game) {
+    if let Some(path) = game.recorder.save_gif() {
+        dev!("Saving recording to {:?}", path);
+    }
+}
+
 #[derive(Clone, Copy)]
 pub struct DebugContext {
     is_on: bool,
     is_drawing_collisionareas: bool,
+    is_drawing_damage_overlay: bool,
+    is_screenshot_requested: bool, // taken after the next render
 }
 impl DebugContext {
     pub fn new() -> Self {
@@ -89,6 +116,8 @@ impl Default for DebugContext {
         DebugContext {
             is_on: false,
             is_drawing_collisionareas: false,
+            is_drawing_damage_overlay: true,
+            is_screenshot_requested: false,
         }
     }
 }
@@ -182,7 +211,7 @@ fn main() {
     );
 
     let dbg_ctx = Rc::new(RefCell::new(DebugContext::new()));
-    let dbg_ctx_render = Rc::new(dbg_ctx.borrow().clone());
+    let dbg_ctx_render = Rc::clone(&dbg_ctx);
     let dbg_ctx_gui = Rc::clone(&dbg_ctx);
     let dbg_ctx_input = Rc::clone(&dbg_ctx);
 
@@ -219,7 +248,28 @@ fn main() {
                 let mut framework = render_ctx.framework.borrow_mut();
                 let mut pixels = render_ctx.pixels.borrow_mut();
 
-                g.game.render(&mut pixels, &dbg_ctx_render, rdt);
+                let blend = g.blending_factor() as f32;
+                g.game
+                    .render(&mut pixels, &dbg_ctx_render.borrow(), rdt, blend);
+
+                if dbg_ctx_render.borrow().is_screenshot_requested {
+                    dbg_ctx_render.borrow_mut().is_screenshot_requested = false;
+                    match save_screenshot(
+                        LOGICAL_WINDOW_WIDTH as u32,
+                        LOGICAL_WINDOW_HEIGHT as u32,
+                        pixels.frame(),
+                    ) {
+                        Ok(path) => {
+                            dev!("Screenshot saved to {:?}", path);
+                        }
+                        Err(e) => eprintln!("{e}"),
+                    }
+                }
+                g.game.recorder.capture(
+                    LOGICAL_WINDOW_WIDTH as u32,
+                    LOGICAL_WINDOW_HEIGHT as u32,
+                    pixels.frame(),
+                );
 
                 let render_timer = &render_ctx.render_timer;
                 let update_timer2 = render_ctx.update_timer.borrow();
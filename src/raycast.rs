@@ -0,0 +1,71 @@
+use hecs::{Entity, World};
+use nalgebra_glm::Vec2;
+
+use crate::components::{CircleColliderCpt, TransformCpt};
+
+// Ray queries against the world's colliders, for hitscan weapons, line of
+// sight and the like
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit {
+    pub entity: Entity,
+    pub point: Vec2,
+    pub distance: f32,
+}
+
+pub trait RayCast {
+    // first collider hit by the ray within max_distance, ignoring one entity
+    // (usually the caster itself)
+    fn cast_ray(
+        &self,
+        origin: Vec2,
+        direction: Vec2,
+        max_distance: f32,
+        ignore: Option<Entity>,
+    ) -> Option<RayHit>;
+}
+
+impl RayCast for World {
+    fn cast_ray(
+        &self,
+        origin: Vec2,
+        direction: Vec2,
+        max_distance: f32,
+        ignore: Option<Entity>,
+    ) -> Option<RayHit> {
+        let direction = direction.normalize();
+        self.query::<(&TransformCpt, &CircleColliderCpt)>()
+            .iter()
+            .filter(|(e, _)| Some(*e) != ignore)
+            .filter_map(|(e, (tx, cc))| {
+                ray_vs_circle(origin, direction, tx.position, cc.r).map(|distance| (e, distance))
+            })
+            .filter(|(_, distance)| *distance <= max_distance)
+            .min_by(|(_, d1), (_, d2)| d1.total_cmp(d2))
+            .map(|(entity, distance)| RayHit {
+                entity,
+                point: origin + direction * distance,
+                distance,
+            })
+    }
+}
+
+// distance along a normalized ray to its first intersection with a circle,
+// 0 when the origin is inside the circle
+pub fn ray_vs_circle(origin: Vec2, direction: Vec2, center: Vec2, r: f32) -> Option<f32> {
+    let to_center = center - origin;
+    if to_center.magnitude_squared() <= r * r {
+        return Some(0.);
+    }
+
+    // closest approach of the ray to the circle center
+    let t_closest = to_center.dot(&direction);
+    if t_closest < 0. {
+        return None;
+    }
+    let d_squared = to_center.magnitude_squared() - t_closest * t_closest;
+    if d_squared > r * r {
+        return None;
+    }
+    Some(t_closest - (r * r - d_squared).sqrt())
+}
@@ -1,4 +1,9 @@
-use crate::{archetypes::*, gfx::pixel::*, LOGICAL_WINDOW_HEIGHT, LOGICAL_WINDOW_WIDTH};
+use crate::{
+    archetypes::*,
+    avatars::{Beacon, MineLayer},
+    gfx::pixel::*,
+    LOGICAL_WINDOW_HEIGHT, LOGICAL_WINDOW_WIDTH,
+};
 use hecs::World;
 use std::time;
 
@@ -104,3 +109,12 @@ pub fn spawn_scenario_shootingallery(world: &mut World) {
         ));
     });
 }
+
+// AI crafts that use their ability slots, and a sprite bodied beacon
+pub fn spawn_scenario_adversaries(world: &mut World) {
+    world.spawn(MineLayer::new());
+    world.spawn(Beacon::new(
+        LOGICAL_WINDOW_WIDTH - 60.,
+        LOGICAL_WINDOW_HEIGHT - 60.,
+    ));
+}
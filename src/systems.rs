@@ -1,15 +1,28 @@
+use std::collections::HashMap;
 use std::time::{self, Duration};
 
-use crate::archetypes::{gen_ping_animation, gen_projectile, ArchProjectile};
-use crate::audio::SoundEffectNames;
+use crate::abilities::AbilityEffect;
+use crate::archetypes::{
+    gen_death_effect, gen_effect, gen_emitted_particle, gen_explosive_projectile,
+    gen_homing_projectile, gen_ping_animation, gen_weapon_projectile, ArchEmittedParticle,
+    ArchExplosiveProjectile, ArchHomingProjectile, ArchProjectile,
+};
+use crate::audio::{detonation_sound, projectile_sounds, SoundEffectNames};
 use crate::game::{RunState, WindowDims};
-use crate::gfx::draw::draw_arcs;
-use crate::gfx::pixel::{RED, WHITE};
-use crate::util::time::Dt;
+use crate::gfx::canvas::Canvas;
+use crate::gfx::draw::rotate_body_offset;
+use crate::gfx::draw_bodies::draw_health_bar;
+use crate::gfx::draw_effects::draw_effect;
+use crate::gfx::pixel::{Color, CYAN, ORANGE, RED, WHITE};
+use crate::gfx::text::{draw_text, text_size};
+use crate::raycast::RayCast;
+use crate::util::time::{now, since, Dt};
+use crate::weapons::{FirePattern, Weapon};
 use crate::{components::*, dev, LOGICAL_WINDOW_HEIGHT, LOGICAL_WINDOW_WIDTH};
 use audio_manager::{AudioPlayback, SoundManager};
 use hecs::{Entity, Query, QueryBorrow, With, Without, World};
 use nalgebra_glm::Vec2;
+use rand::Rng;
 use winit::event::VirtualKeyCode;
 use winit_input_helper::WinitInputHelper;
 
@@ -55,10 +68,40 @@ pub fn system_process_human_input(world: &mut World, runstate: RunState, input:
             } else {
                 projectile_emitter.intends_to_fire = false;
             }
+
+            if input.key_pressed(VirtualKeyCode::Q) {
+                projectile_emitter.cycle_weapon();
+            }
+        }
+    }
+
+    for (_id, craft_action) in world.query_mut::<With<&mut CraftActionStateCpt, &HumanInputCpt>>() {
+        if runstate == RunState::Running {
+            craft_action.is_firing_secondary =
+                input.key_pressed(VirtualKeyCode::E) || input.key_held(VirtualKeyCode::E);
         }
     }
 }
 
+// minds sense nearby human crafts and react with their secondary
+pub fn system_process_ai_input(world: &mut World) {
+    let human_positions: Vec<Vec2> = world
+        .query::<With<&TransformCpt, &HumanInputCpt>>()
+        .iter()
+        .map(|(_e, tx)| tx.position)
+        .collect();
+
+    for (_id, (transform, mind, craft_action)) in world.query_mut::<Without<
+        (&TransformCpt, &mut MindStateCpt, &mut CraftActionStateCpt),
+        &HumanInputCpt,
+    >>() {
+        mind.is_sensing = human_positions
+            .iter()
+            .any(|pos| (pos - transform.position).magnitude() <= mind.sense_range);
+        craft_action.is_firing_secondary = mind.is_sensing;
+    }
+}
+
 fn set_rigidbody_component(
     transform: &TransformCpt,
     rotational_input: &RotationalInputCpt,
@@ -130,37 +173,402 @@ fn set_rotational_input_component_by_human(
 
 pub fn system_projectile_emission(world: &mut World) {
     let mut projectiles_to_spawn: Vec<ArchProjectile> = vec![];
+    let mut homing_projectiles_to_spawn: Vec<ArchHomingProjectile> = vec![];
     let mut sound_effects: Vec<SoundEffectEvent> = vec![];
     for (ent, (tx, pe, cc)) in
         world.query_mut::<(&TransformCpt, &mut ProjectileEmitterCpt, &CircleColliderCpt)>()
     {
-        if pe.intends_to_fire {
-            let last_emit = pe.last_emission_time;
-            if last_emit.elapsed().as_millis() as i32 >= pe.cooldown {
-                pe.last_emission_time = time::Instant::now();
-                let dx_theta = (tx.heading).cos();
-                let dy_theta = (tx.heading).sin();
+        let weapon = pe.weapon.spec();
+        let since_last_emit = since(pe.last_emission_time).as_millis() as i32;
+
+        // (angle offsets from heading, charge factor) of the volley fired this tick
+        let volley: Option<(Vec<f32>, f32)> = match weapon.pattern {
+            FirePattern::Single => {
+                if pe.intends_to_fire && since_last_emit >= weapon.cooldown {
+                    Some((vec![0.], 1.))
+                } else {
+                    None
+                }
+            }
+            FirePattern::Spread { n, arc } => {
+                if pe.intends_to_fire && since_last_emit >= weapon.cooldown {
+                    Some((spread_offsets(n, arc), 1.))
+                } else {
+                    None
+                }
+            }
+            FirePattern::Burst { n, interval } => {
+                // a started burst finishes even if fire is released
+                if pe.burst_remaining > 0 && since_last_emit >= interval {
+                    pe.burst_remaining -= 1;
+                    Some((vec![0.], 1.))
+                } else if pe.burst_remaining == 0
+                    && pe.intends_to_fire
+                    && since_last_emit >= weapon.cooldown
+                {
+                    pe.burst_remaining = n.saturating_sub(1);
+                    Some((vec![0.], 1.))
+                } else {
+                    None
+                }
+            }
+            FirePattern::Charge {
+                max_charge,
+                max_factor,
+            } => match (pe.intends_to_fire, pe.charge_start) {
+                (true, None) if since_last_emit >= weapon.cooldown => {
+                    pe.charge_start = Some(now());
+                    None
+                }
+                (false, Some(charge_start)) => {
+                    pe.charge_start = None;
+                    let charge =
+                        (since(charge_start).as_millis() as f32 / max_charge as f32).clamp(0., 1.);
+                    Some((vec![0.], 1. + (max_factor - 1.) * charge))
+                }
+                _ => None,
+            },
+            // hitscan, see system_beam_emission
+            FirePattern::Beam { .. } => None,
+        };
+
+        if let Some((offsets, factor)) = volley {
+            pe.last_emission_time = now();
+            for offset in offsets {
+                let theta = tx.heading.get() + offset;
+                let dx_theta = theta.cos();
+                let dy_theta = theta.sin();
                 let x = tx.position.x + dx_theta * cc.r;
                 let y = tx.position.y + dy_theta * cc.r;
-                let vx = pe.projectile_speed * dx_theta;
-                let vy = pe.projectile_speed * dy_theta;
-                let projectile =
-                    gen_projectile(x, y, vx, vy, time::Duration::new(3, 0), pe.hit_damage, RED);
-                projectiles_to_spawn.push(projectile);
-                sound_effects.push(SoundEffectEvent {
-                    name: SoundEffectNames::Photon,
-                });
+                let vx = weapon.projectile_speed * factor * dx_theta;
+                let vy = weapon.projectile_speed * factor * dy_theta;
+                let hit_damage = (weapon.hit_damage as f32 * factor).round() as i32;
+                match weapon.projectile {
+                    ProjectileKind::Missile => {
+                        let mut projectile =
+                            gen_homing_projectile(&weapon, x, y, vx, vy, hit_damage);
+                        projectile.3.owner = Some(ent);
+                        projectile.3.is_friendly = pe.is_friendly;
+                        homing_projectiles_to_spawn.push(projectile);
+                    }
+                    _ => {
+                        let mut projectile =
+                            gen_weapon_projectile(&weapon, x, y, vx, vy, hit_damage);
+                        projectile.3.owner = Some(ent);
+                        projectile.3.is_friendly = pe.is_friendly;
+                        projectiles_to_spawn.push(projectile);
+                    }
+                }
+            }
+            if let Some(name) = projectile_sounds(weapon.projectile).fire {
+                sound_effects.push(SoundEffectEvent { name });
             }
         }
     }
     for projectile in projectiles_to_spawn {
         world.spawn(projectile);
     }
+    for projectile in homing_projectiles_to_spawn {
+        world.spawn(projectile);
+    }
     for x in sound_effects {
         world.spawn((x,));
     }
 }
 
+pub fn system_beam_emission(world: &mut World, dt: &Dt) {
+    // (emitter, beam origin, beam direction, weapon)
+    let mut beams: Vec<(Entity, Vec2, Vec2, Weapon)> = vec![];
+    let mut sound_effects: Vec<SoundEffectEvent> = vec![];
+    for (ent, (tx, pe, cc)) in
+        world.query_mut::<(&TransformCpt, &mut ProjectileEmitterCpt, &CircleColliderCpt)>()
+    {
+        let weapon = pe.weapon.spec();
+        if matches!(weapon.pattern, FirePattern::Beam { .. }) && pe.intends_to_fire {
+            if pe.beam.is_none() {
+                if let Some(name) = projectile_sounds(weapon.projectile).fire {
+                    sound_effects.push(SoundEffectEvent { name });
+                }
+            }
+            let direction = Vec2::new(tx.heading.cos(), tx.heading.sin());
+            beams.push((ent, tx.position + direction * cc.r, direction, weapon));
+        } else {
+            pe.beam = None;
+            pe.beam_damage_accum = 0.;
+        }
+    }
+
+    let mut damage_events: Vec<PhysicalDamageEvent> = vec![];
+    for (ent, origin, direction, weapon) in beams {
+        let FirePattern::Beam { dps, range } = weapon.pattern else {
+            continue;
+        };
+        let hit = world.cast_ray(origin, direction, range, Some(ent));
+        let mut pe = world.get::<&mut ProjectileEmitterCpt>(ent).unwrap();
+        pe.beam = Some((
+            origin,
+            hit.map(|h| h.point).unwrap_or(origin + direction * range),
+        ));
+
+        match hit {
+            Some(hit) if world.get::<&HealthCpt>(hit.entity).is_ok() => {
+                // damage is dealt in hit_damage sized chunks at dps rate
+                pe.beam_damage_accum += dps * dt.secs();
+                if pe.beam_damage_accum >= weapon.hit_damage as f32 {
+                    pe.beam_damage_accum -= weapon.hit_damage as f32;
+                    damage_events.push(PhysicalDamageEvent {
+                        receiver: hit.entity,
+                        source: Some(ent),
+                        damage: weapon.hit_damage,
+                        kind: weapon.projectile.damage_type(),
+                        position: hit.point,
+                    });
+                }
+            }
+            _ => {
+                pe.beam_damage_accum = 0.;
+            }
+        }
+    }
+
+    for x in damage_events {
+        world.spawn((x,));
+    }
+    for x in sound_effects {
+        world.spawn((x,));
+    }
+}
+
+// angle offsets for n projectiles fanned evenly across arc, centered on 0
+fn spread_offsets(n: u32, arc: f32) -> Vec<f32> {
+    if n <= 1 {
+        return vec![0.];
+    }
+    (0..n)
+        .map(|i| -arc / 2. + arc * i as f32 / (n - 1) as f32)
+        .collect()
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Abilities
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn system_ability_activation(world: &mut World) {
+    let mut explosives_to_spawn: Vec<ArchExplosiveProjectile> = vec![];
+    let mut detonations: Vec<DetonationEvent> = vec![];
+    let mut sound_effects: Vec<SoundEffectEvent> = vec![];
+    for (ent, (tx, craft_action, slot, cc)) in world.query_mut::<(
+        &TransformCpt,
+        &CraftActionStateCpt,
+        &mut AbilitySlotCpt,
+        &CircleColliderCpt,
+    )>() {
+        let ability = slot.ability.spec();
+        if !craft_action.is_firing_secondary
+            || slot.ammo == 0
+            || (since(slot.last_activation_time).as_millis() as i32) < ability.cooldown
+        {
+            continue;
+        }
+        slot.ammo -= 1;
+        slot.last_activation_time = now();
+
+        let dx_theta = tx.heading.cos();
+        let dy_theta = tx.heading.sin();
+        let (delivered, offset, speed, duration) = match ability.effect {
+            AbilityEffect::Launch {
+                projectile,
+                speed,
+                duration,
+            } => (Some(projectile), cc.r, speed, duration),
+            AbilityEffect::Drop {
+                projectile,
+                duration,
+            } => (Some(projectile), -cc.r, 0., duration),
+            AbilityEffect::Pulse => {
+                detonations.push(DetonationEvent {
+                    position: tx.position,
+                    radius: ability.blast_radius,
+                    damage: ability.blast_damage,
+                    owner: Some(ent),
+                    kind: None,
+                });
+                (None, 0., 0., time::Duration::ZERO)
+            }
+        };
+
+        if let Some(projectile) = delivered {
+            let mut explosive = gen_explosive_projectile(
+                &ability,
+                projectile,
+                tx.position.x + dx_theta * offset,
+                tx.position.y + dy_theta * offset,
+                speed * dx_theta,
+                speed * dy_theta,
+                duration,
+            );
+            explosive.3.owner = Some(ent);
+            explosives_to_spawn.push(explosive);
+            if let Some(name) = projectile_sounds(projectile).fire {
+                sound_effects.push(SoundEffectEvent { name });
+            }
+        }
+    }
+    for explosive in explosives_to_spawn {
+        world.spawn(explosive);
+    }
+    for x in detonations {
+        world.spawn((x,));
+    }
+    for x in sound_effects {
+        world.spawn((x,));
+    }
+}
+
+// blasts damage every body they reach, except their owner
+pub fn system_detonation(world: &mut World) {
+    let detonations: Vec<(Entity, DetonationEvent)> = world
+        .query::<&DetonationEvent>()
+        .iter()
+        .map(|(e, ev)| (e, *ev))
+        .collect();
+
+    let mut damage_events: Vec<PhysicalDamageEvent> = vec![];
+    let mut rings_to_spawn = vec![];
+    for (_ent, detonation) in detonations.iter() {
+        for (receiver, (tx, cc)) in world
+            .query::<With<(&TransformCpt, &CircleColliderCpt), &HealthCpt>>()
+            .iter()
+        {
+            let to_blast = detonation.position - tx.position;
+            if Some(receiver) != detonation.owner
+                && to_blast.magnitude() <= detonation.radius + cc.r
+            {
+                // hit on the side of the body facing the blast
+                let position = if to_blast.magnitude() > cc.r {
+                    tx.position + to_blast.normalize() * cc.r
+                } else {
+                    detonation.position
+                };
+                damage_events.push(PhysicalDamageEvent {
+                    receiver,
+                    source: detonation.owner,
+                    damage: detonation.damage,
+                    kind: DamageType::Explosive,
+                    position,
+                });
+            }
+        }
+
+        rings_to_spawn.push(gen_effect(
+            EffectKind::ExplosionRing,
+            detonation.position.x,
+            detonation.position.y,
+            detonation.radius,
+            ORANGE,
+        ));
+    }
+
+    for (ent, detonation) in detonations.iter() {
+        world.despawn(*ent);
+        if let Some(name) = detonation_sound(detonation.kind) {
+            world.spawn((SoundEffectEvent { name },));
+        }
+    }
+    for x in damage_events {
+        world.spawn((x,));
+    }
+    for ring in rings_to_spawn {
+        world.spawn(ring);
+    }
+}
+
+// explosive entities leave a DetonationEvent behind when despawned, returns
+// whether ent was one
+fn detonate_if_explosive(world: &mut World, ent: Entity) -> bool {
+    let detonation =
+        match world.query_one_mut::<(&TransformCpt, &ExplosiveCpt, Option<&ProjectileCpt>)>(ent) {
+            Ok((tx, explosive, projectile)) => DetonationEvent {
+                position: tx.position,
+                radius: explosive.radius,
+                damage: explosive.damage,
+                owner: projectile.and_then(|p| p.owner),
+                kind: projectile.map(|p| p.kind),
+            },
+            Err(_) => return false,
+        };
+    world.spawn((detonation,));
+    true
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Homing
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn system_homing(world: &mut World, dt: &Dt) {
+    // bodies that can be homed in on: (entity, position, is_human)
+    let targets: Vec<(Entity, Vec2, bool)> = world
+        .query::<With<(&TransformCpt, Option<&HumanInputCpt>), (&CircleColliderCpt, &HealthCpt)>>()
+        .iter()
+        .map(|(e, (tx, human))| (e, tx.position, human.is_some()))
+        .collect();
+
+    for (_id, (transform, rigidbody, homing, projectile)) in world.query_mut::<(
+        &mut TransformCpt,
+        &mut RigidBodyCpt,
+        &mut HomingCpt,
+        &ProjectileCpt,
+    )>() {
+        // lose despawned targets
+        if let Some(target) = homing.target {
+            if !targets.iter().any(|(e, _, _)| *e == target) {
+                homing.target = None;
+            }
+        }
+
+        // follow velocity, eg after a boundary bounce
+        let heading = rigidbody.velocity.y.atan2(rigidbody.velocity.x);
+
+        if homing.target.is_none() && (!homing.has_acquired || homing.is_retargeting) {
+            // nearest target within range and inside the cone ahead
+            homing.target = targets
+                .iter()
+                .filter(|(_, _, is_human)| *is_human != projectile.is_friendly)
+                .map(|(e, pos, _)| (*e, *pos - transform.position))
+                .filter(|(_, d)| {
+                    d.magnitude() <= homing.range
+                        && wrap_angle(d.y.atan2(d.x) - heading).abs() <= homing.cone
+                })
+                .min_by(|(_, d1), (_, d2)| d1.magnitude().total_cmp(&d2.magnitude()))
+                .map(|(e, _)| e);
+            homing.has_acquired |= homing.target.is_some();
+        }
+
+        let mut new_heading = heading;
+        if let Some(target_pos) = homing
+            .target
+            .and_then(|t| targets.iter().find(|(e, _, _)| *e == t))
+            .map(|(_, pos, _)| *pos)
+        {
+            // turn toward target, limited by turn rate
+            let d = target_pos - transform.position;
+            let max_turn = homing.turn_rate * dt.secs();
+            new_heading += wrap_angle(d.y.atan2(d.x) - heading).clamp(-max_turn, max_turn);
+        }
+
+        let speed = rigidbody.velocity.magnitude();
+        rigidbody.velocity = Vec2::new(new_heading.cos(), new_heading.sin()) * speed;
+        transform.heading.set(new_heading);
+    }
+}
+
+// wraps an angle to [-pi, pi]
+fn wrap_angle(theta: f32) -> f32 {
+    let two_pi = nalgebra_glm::two_pi::<f32>();
+    let wrapped = (theta + nalgebra_glm::pi::<f32>()).rem_euclid(two_pi);
+    wrapped - nalgebra_glm::pi::<f32>()
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // Integrate for Motion
 ////////////////////////////////////////////////////////////////////////////////
@@ -182,63 +590,382 @@ pub fn system_integrate_translation(world: &mut World, dt: &Dt) {
     }
 }
 
+// advances orbiters along their ellipse and places them around the center
 pub fn system_integrate_orbiting_particles(world: &mut World, dt: &Dt) {
-    let mut new_orbitpart_cpts: Vec<(Entity, TransformCpt, OrbitParticleCpt)> = vec![];
-    {
-        let mut query_orbitparts = world.query::<(&TransformCpt, &OrbitParticleCpt)>();
-        new_orbitpart_cpts = query_orbitparts
-            .iter()
-            .map(|(ent, (transform, orbitpart))| (ent, transform.clone(), orbitpart.clone()))
-            .collect::<Vec<_>>();
-    }
+    let orbiters: Vec<(Entity, OrbitParticleCpt, Vec2)> = world
+        .query_mut::<(&OrbitParticleCpt, &TransformCpt)>()
+        .into_iter()
+        .map(|(ent, (orbit, transform))| (ent, *orbit, transform.position))
+        .collect();
+
+    for (ent, mut orbit, position) in orbiters {
+        let center = match orbit.center {
+            OrbitCenter::Point(point) => point,
+            OrbitCenter::Body(parent) => {
+                match world.get::<&TransformCpt>(parent).map(|t| t.position) {
+                    Ok(parent_position) => parent_position,
+                    Err(_) => match orbit.on_center_despawn {
+                        OrphanPolicy::Despawn => {
+                            let _ = world.despawn(ent);
+                            continue;
+                        }
+                        OrphanPolicy::Detach => {
+                            // keep circling the spot the parent left
+                            let point = position - orbit.offset();
+                            orbit.center = OrbitCenter::Point(point);
+                            point
+                        }
+                    },
+                }
+            }
+        };
 
-    for (ent_orbitpart, transform, mut orbitpart) in new_orbitpart_cpts.iter() {
         // ang_vel = vel / r
-        // ang = ang_vel * period (dt)
-        let ang_vel = orbitpart.speed / orbitpart.r;
-        let d_ang = ang_vel * dt.0.as_secs_f32();
-        let new_angle = orbitpart.angle.get() + d_ang;
-
-        // A. d_transform based on parent entity
-        match orbitpart.attached_to {
-            Some(entity) => {
-                if let Ok(attached_transform) = world.query_one_mut::<&TransformCpt>(entity) {
-                    let new_orbitpart_transform_cpt = TransformCpt {
-                        position: Vec2::new(
-                            attached_transform.position.x + orbitpart.r * new_angle.cos(),
-                            attached_transform.position.y + orbitpart.r * new_angle.sin(),
-                        ),
-                        heading: transform.heading,
-                        scale: transform.scale,
-                    };
-                    world.exchange_one::<TransformCpt, TransformCpt>(
-                        *ent_orbitpart,
-                        new_orbitpart_transform_cpt,
-                    );
-                    let mut orbitpart_cpt = world
-                        .query_one_mut::<(&mut OrbitParticleCpt)>(*ent_orbitpart)
-                        .unwrap();
-                    orbitpart_cpt.angle.set(new_angle);
-                } else {
-                    orbitpart.attached_to = None;
+        let ang_vel = orbit.speed / orbit.r.max(f32::EPSILON);
+        orbit
+            .angle
+            .set(wrap_angle(orbit.angle.get() + ang_vel * dt.secs()));
+
+        if let Ok((transform, orbit_cpt)) =
+            world.query_one_mut::<(&mut TransformCpt, &mut OrbitParticleCpt)>(ent)
+        {
+            transform.position = center + orbit.offset();
+            *orbit_cpt = orbit;
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Particle Emission
+////////////////////////////////////////////////////////////////////////////////
+
+// runs after damage resolution, so hits of this tick burst right away
+pub fn system_particle_emission(world: &mut World, dt: &Dt) {
+    let mut rng = rand::thread_rng();
+    let mut particles: Vec<ArchEmittedParticle> = vec![];
+    for (_ent, (emitters, transform, rigidbody, rotational_input)) in world.query_mut::<(
+        &mut ParticleEmitterCpt,
+        &TransformCpt,
+        Option<&RigidBodyCpt>,
+        Option<&RotationalInputCpt>,
+    )>() {
+        let velocity = rigidbody.map_or(Vec2::zeros(), |rb| rb.velocity);
+        let is_thrusting = rotational_input.is_some_and(|input| input.is_thrusting);
+        let hits = std::mem::take(&mut emitters.hits);
+        let heading = transform.heading.get();
+
+        for emitter in emitters.emitters.iter_mut() {
+            let inherited = velocity * emitter.inherit_velocity;
+            match emitter.trigger {
+                EmitTrigger::Continuous | EmitTrigger::WhileThrusting => {
+                    if emitter.trigger == EmitTrigger::WhileThrusting && !is_thrusting {
+                        emitter.accumulator = 0.;
+                        continue;
+                    }
+                    let origin = transform.position + rotate_body_offset(emitter.offset, heading);
+                    emitter.accumulator += emitter.rate * dt.secs();
+                    while emitter.accumulator >= 1. {
+                        emitter.accumulator -= 1.;
+                        let direction = heading + emitter.direction;
+                        particles.push(emit_particle(
+                            emitter, origin, direction, inherited, &mut rng,
+                        ));
+                    }
+                }
+                EmitTrigger::OnHit { count } => {
+                    for hit in hits.iter() {
+                        let away = hit - transform.position;
+                        let direction = if away == Vec2::zeros() {
+                            heading
+                        } else {
+                            away.y.atan2(away.x)
+                        };
+                        for _ in 0..count {
+                            particles
+                                .push(emit_particle(emitter, *hit, direction, inherited, &mut rng));
+                        }
+                    }
                 }
             }
-            None => {
-                // B. d_transform by first calculating the center of rotation coords (for rotating around nothingness)
-                // let x_center = transform.position.x - orbitpart.r * orbitpart.angle.cos();
-                // let y_center = transform.position.y - orbitpart.r * orbitpart.angle.sin();
-
-                // TransformCpt {
-                //     position: Vec2::new(
-                //         x_center + orbitpart.r * new_angle.cos(),
-                //         y_center + orbitpart.r * new_angle.sin(),
-                //     ),
-                //     heading: transform.heading,
-                //     scale: transform.scale,
-                // }
-                // C. like B but decidedly has nothing to rotate around and so remains in position.
+        }
+    }
+    world.spawn_batch(particles);
+}
+
+// one particle into the emitter's cone around direction, at random within its
+// speed and lifetime ranges
+fn emit_particle(
+    emitter: &ParticleEmitter,
+    origin: Vec2,
+    direction: f32,
+    inherited: Vec2,
+    rng: &mut impl Rng,
+) -> ArchEmittedParticle {
+    let angle = direction + (rng.gen::<f32>() - 0.5) * emitter.spread;
+    let speed = emitter.speed.0 + (emitter.speed.1 - emitter.speed.0) * rng.gen::<f32>();
+    let lifetime =
+        emitter.lifetime.0 + (emitter.lifetime.1 - emitter.lifetime.0) * rng.gen::<f32>();
+    let velocity = Vec2::new(angle.cos(), angle.sin()) * speed + inherited;
+    gen_emitted_particle(origin, velocity, lifetime, emitter.colors)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Tweens
+////////////////////////////////////////////////////////////////////////////////
+
+// advances tweens and writes their values into the components they target,
+// targets the entity lacks are skipped
+pub fn system_tweens(world: &mut World, dt: &Dt) {
+    let mut finished: Vec<TweenFinishedEvent> = vec![];
+    for (ent, (tweens, mut drawbody, mut transform, mut parent)) in world.query_mut::<(
+        &mut TweenCpt,
+        Option<&mut DrawBodyCpt>,
+        Option<&mut TransformCpt>,
+        Option<&mut ParentCpt>,
+    )>() {
+        for tween in tweens.tweens.iter_mut() {
+            tween.elapsed += dt.secs();
+            let t = tween.progress();
+            match (tween.target, drawbody.as_deref_mut()) {
+                (TweenTarget::Color(from, to), Some(drawbody)) => {
+                    drawbody.colorbody.primary = from.lerp(to, t);
+                }
+                (TweenTarget::Alpha(from, to), Some(drawbody)) => {
+                    let [r, g, b, _a] = *drawbody.colorbody.primary.as_bytes();
+                    let alpha = (from + (to - from) * t).clamp(0., 1.);
+                    drawbody.colorbody.primary = Color::RGBA(r, g, b, (alpha * 255.).round() as u8);
+                }
+                (TweenTarget::Radius(from, to), Some(drawbody)) => {
+                    if let DrawData::R(r) = &mut drawbody.data {
+                        *r = (from + (to - from) * t).max(0.);
+                    }
+                }
+                _ => {}
             }
-        };
+            match (tween.target, transform.as_deref_mut()) {
+                (TweenTarget::Scale(from, to), Some(transform)) => {
+                    transform.scale = from + (to - from) * t;
+                }
+                (TweenTarget::Offset(from, to), Some(transform)) => {
+                    let offset = from + (to - from) * t;
+                    match parent.as_deref_mut() {
+                        Some(parent) => parent.offset = offset,
+                        None => transform.position += offset - tween.applied_offset,
+                    }
+                    tween.applied_offset = offset;
+                }
+                _ => {}
+            }
+            if tween.is_finished() {
+                finished.push(TweenFinishedEvent {
+                    entity: ent,
+                    target: tween.target,
+                });
+            }
+        }
+        tweens.tweens.retain(|tween| !tween.is_finished());
+    }
+    for event in finished {
+        world.spawn((event,));
+    }
+}
+
+// runs late, so systems before it can react to finished tweens
+pub fn system_tween_finished_events(world: &mut World) {
+    let events: Vec<(Entity, TweenFinishedEvent)> = world
+        .query::<&TweenFinishedEvent>()
+        .iter()
+        .map(|(e, ev)| (e, *ev))
+        .collect();
+
+    for (event_ent, event) in events {
+        let is_done = world
+            .get::<&TweenCpt>(event.entity)
+            .is_ok_and(|tweens| tweens.is_despawned_when_done && tweens.tweens.is_empty());
+        if is_done {
+            let _ = world.despawn(event.entity);
+        }
+        let _ = world.despawn(event_ent);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Transform Hierarchy
+////////////////////////////////////////////////////////////////////////////////
+
+// places children relative to their parents, after everything has moved.
+// Parents are done before their children, so chains of any length settle in one
+// pass. Children of despawned parents are despawned or detached, their own
+// children follow within the same pass
+pub fn system_propagate_transforms(world: &mut World) {
+    let links: HashMap<Entity, ParentCpt> = world
+        .query_mut::<&ParentCpt>()
+        .into_iter()
+        .map(|(ent, parent)| (ent, *parent))
+        .collect();
+
+    let mut children: Vec<(usize, Entity, ParentCpt)> = links
+        .iter()
+        .map(|(ent, link)| (hierarchy_depth(&links, *ent), *ent, *link))
+        .collect();
+    children.sort_by_key(|(depth, _ent, _link)| *depth);
+
+    for (_depth, child, link) in children {
+        let parent_transform = world.get::<&TransformCpt>(link.parent).map(|t| *t);
+        match parent_transform {
+            Ok(parent) => {
+                if let Ok(transform) = world.query_one_mut::<&mut TransformCpt>(child) {
+                    transform.position =
+                        parent.position + rotate_body_offset(link.offset, parent.heading.get());
+                    transform
+                        .heading
+                        .set(wrap_angle(parent.heading.get() + link.rotation));
+                }
+            }
+            Err(_) => match link.on_parent_despawn {
+                OrphanPolicy::Despawn => {
+                    let _ = world.despawn(child);
+                }
+                OrphanPolicy::Detach => {
+                    let _ = world.remove_one::<ParentCpt>(child);
+                }
+            },
+        }
+    }
+}
+
+// steps up to the root, a parent cycle stops after visiting every link once
+fn hierarchy_depth(links: &HashMap<Entity, ParentCpt>, ent: Entity) -> usize {
+    let mut depth = 0;
+    let mut current = ent;
+    while let Some(link) = links.get(&current) {
+        depth += 1;
+        if depth > links.len() {
+            break;
+        }
+        current = link.parent;
+    }
+    depth
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Render Interpolation
+////////////////////////////////////////////////////////////////////////////////
+
+// runs first thing in an update, before anything moves
+pub fn system_remember_transforms(world: &mut World) {
+    let mut new_bodies: Vec<(Entity, TransformCpt)> = vec![];
+    for (ent, (transform, previous)) in
+        world.query_mut::<(&TransformCpt, Option<&mut PreviousTransformCpt>)>()
+    {
+        match previous {
+            Some(previous) => previous.0 = *transform,
+            None => new_bodies.push((ent, *transform)),
+        }
+    }
+    for (ent, transform) in new_bodies {
+        let _ = world.insert_one(ent, PreviousTransformCpt(transform));
+    }
+}
+
+// Render draws between the last two update ticks, blend is how far along (0..1)
+// the next tick is. Transforms are swapped for their interpolated ones for the
+// length of a render, everything drawn from a TransformCpt follows. Returns the
+// ticked transforms to restore afterwards
+pub fn system_interpolate_transforms(world: &mut World, blend: f32) -> Vec<(Entity, TransformCpt)> {
+    if blend >= 1. {
+        return vec![];
+    }
+    let mut ticked = vec![];
+    for (ent, (transform, previous)) in
+        world.query_mut::<(&mut TransformCpt, &PreviousTransformCpt)>()
+    {
+        ticked.push((ent, *transform));
+        *transform = previous.0.lerp(transform, blend);
+    }
+    ticked
+}
+
+pub fn system_restore_transforms(world: &mut World, ticked: Vec<(Entity, TransformCpt)>) {
+    for (ent, transform) in ticked {
+        if let Ok(mut current) = world.get::<&mut TransformCpt>(ent) {
+            *current = transform;
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Lifetimes
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn system_projectile_lifetime(world: &mut World) {
+    let mut expired_projectiles: Vec<(Entity, ProjectileKind)> = vec![];
+    let mut sound_effects: Vec<SoundEffectEvent> = vec![];
+    for (ent, projectile) in world.query_mut::<&ProjectileCpt>() {
+        if since(projectile.start_time) >= projectile.duration {
+            expired_projectiles.push((ent, projectile.kind));
+        }
+    }
+    for (ent, kind) in expired_projectiles {
+        // a detonation sounds the expire itself
+        if !detonate_if_explosive(world, ent) {
+            if let Some(name) = projectile_sounds(kind).expire {
+                sound_effects.push(SoundEffectEvent { name });
+            }
+        }
+        world.despawn(ent);
+    }
+    for x in sound_effects {
+        world.spawn((x,));
+    }
+}
+
+pub fn system_particle_lifetime(world: &mut World, dt: &Dt) {
+    let mut expired_particles: Vec<Entity> = vec![];
+    for (ent, (particle, drawbody)) in
+        world.query_mut::<(&mut ParticleLifetimeCpt, &mut DrawBodyCpt)>()
+    {
+        particle.age += dt.secs();
+        if particle.age >= particle.lifetime {
+            expired_particles.push(ent);
+            continue;
+        }
+        let (birth, death) = particle.colors;
+        drawbody.colorbody.primary = birth.lerp(death, particle.age / particle.lifetime);
+    }
+    for ent in expired_particles {
+        world.despawn(ent);
+    }
+}
+
+pub fn system_damage_overlay_lifetime(world: &mut World) {
+    let mut faded_overlays: Vec<Entity> = vec![];
+    for (ent, overlay) in world.query_mut::<&DamageOverlayCpt>() {
+        if since(overlay.last_damage_time) >= DamageOverlayCpt::DURATION {
+            faded_overlays.push(ent);
+        }
+    }
+    for ent in faded_overlays {
+        world.remove_one::<DamageOverlayCpt>(ent);
+    }
+
+    let mut expired_numbers: Vec<Entity> = vec![];
+    for (ent, number) in world.query_mut::<&DamageNumberCpt>() {
+        if since(number.start_time) >= DamageNumberCpt::DURATION {
+            expired_numbers.push(ent);
+        }
+    }
+    for ent in expired_numbers {
+        world.despawn(ent);
+    }
+}
+
+pub fn system_shield_regeneration(world: &mut World, dt: &Dt) {
+    for (_id, shield) in world.query_mut::<&mut ShieldCpt>() {
+        if since(shield.last_hit_time) >= shield.regen_delay {
+            shield.strength =
+                (shield.strength + shield.regen_rate * dt.secs()).min(shield.max_strength);
+        }
     }
 }
 
@@ -313,12 +1040,10 @@ pub fn system_boundary_restrict_particletypes(world: &mut World) {
 // tmp for development, keep avatars in view
 pub fn test_system_boundary_restrict_particle(world: &mut World) {
     let mut pings_to_spawn = vec![];
-    for (id, (transform, rigidbody)) in
-        world.query_mut::<Without<
-            (&mut TransformCpt, &mut RigidBodyCpt),
-            (&ParticleColliderCpt, &CircleColliderCpt),
-        >>()
-    {
+    for (id, (transform, rigidbody)) in world.query_mut::<Without<
+        Without<(&mut TransformCpt, &mut RigidBodyCpt), &ParticleLifetimeCpt>,
+        (&ParticleColliderCpt, &CircleColliderCpt),
+    >>() {
         if transform.position.x >= LOGICAL_WINDOW_WIDTH || transform.position.x < 0f32 {
             rigidbody.velocity.x = -rigidbody.velocity.x;
 
@@ -428,6 +1153,12 @@ pub fn system_collision_resolution(world: &mut World) {
             let ent_a = collision_event.a;
             let ent_b = collision_event.b;
 
+            // Projectiles pass through their owner
+            if is_projectile_owner(world, ent_a, ent_b) || is_projectile_owner(world, ent_b, ent_a)
+            {
+                continue;
+            }
+
             // Resolve projectile vs circloid
             if (world.get::<&ParticleColliderCpt>(ent_a).is_ok()
                 && world.get::<&CircleColliderCpt>(ent_b).is_ok()
@@ -462,48 +1193,125 @@ pub fn system_collision_resolution(world: &mut World) {
     // }
     for (sender, receiver) in physical_damage_particles_circloids.into_iter() {
         dev!("creating phys dmg event");
-        let damage;
+        let damage_event;
+        let hit_sound;
         {
             let projectile = world.get::<&ProjectileCpt>(sender).unwrap();
-            damage = projectile.hit_damage;
+            let transform = world.get::<&TransformCpt>(sender).unwrap();
+            damage_event = PhysicalDamageEvent {
+                receiver,
+                source: projectile.owner,
+                damage: projectile.hit_damage,
+                kind: projectile.kind.damage_type(),
+                position: transform.position,
+            };
+            hit_sound = projectile_sounds(projectile.kind).hit;
+        }
+        world.spawn((damage_event,));
+        if let Some(name) = hit_sound {
+            world.spawn((SoundEffectEvent { name },));
         }
-        world.spawn((PhysicalDamageEvent { receiver, damage },));
     }
 
     for ent in ents_to_despawn {
+        detonate_if_explosive(world, ent);
         world.despawn(ent);
     }
 }
 
+fn is_projectile_owner(world: &World, projectile: Entity, owner: Entity) -> bool {
+    world
+        .get::<&ProjectileCpt>(projectile)
+        .map(|p| p.owner == Some(owner))
+        .unwrap_or(false)
+}
+
 pub fn system_physical_damage_resolution(world: &mut World) {
-    // apply projectile damage to avatars
-    let mut apply_damage: Vec<(Entity, Entity, i32)> = world
+    // apply damage events to avatars: armor, then shields, then health
+    let mut apply_damage: Vec<(Entity, PhysicalDamageEvent)> = world
         .query::<&PhysicalDamageEvent>()
         .iter()
-        .map(|(e, (ev))| (e, ev.receiver, ev.damage))
+        .map(|(e, ev)| (e, *ev))
         .collect();
 
     let mut sound_effects_to_play: Vec<(SoundEffectEvent)> = vec![];
 
     let mut killed_bodies: Vec<Entity> = vec![];
-    for (ent, receiver, dmg) in apply_damage.iter() {
-        // * query_one, not query_one_mut nor get
-        let mut query = world.query_one::<&mut HealthCpt>(*receiver).unwrap();
-        let health = query.get().unwrap();
-        health.hp -= dmg;
+    let mut damaged_bodies: Vec<(Entity, Vec2)> = vec![];
+    let mut damage_numbers: Vec<(DamageNumberCpt, TransformCpt)> = vec![];
+    for (ent, ev) in apply_damage.iter() {
+        let Ok((health, shield, armor)) =
+            world.query_one_mut::<(&mut HealthCpt, Option<&mut ShieldCpt>, Option<&ArmorCpt>)>(
+                ev.receiver,
+            )
+        else {
+            continue;
+        };
+
+        let mut damage = ev.damage as f32;
+        if let Some(armor) = armor {
+            damage *= 1. - armor.resistance(ev.kind);
+        }
+        let dealt = damage.round() as i32;
+        if let Some(shield) = shield {
+            let absorbed = damage.min(shield.strength);
+            shield.strength -= absorbed;
+            shield.last_hit_time = now();
+            damage -= absorbed;
+        }
+        let hull_damage = damage.round() as i32;
+
+        // number shows damage after armor, cyan when the shield took all of it
+        if dealt > 0 {
+            damage_numbers.push((
+                DamageNumberCpt {
+                    value: dealt,
+                    color: if hull_damage > 0 { WHITE } else { CYAN },
+                    start_time: now(),
+                },
+                TransformCpt {
+                    position: ev.position,
+                    ..TransformCpt::new()
+                },
+            ));
+        }
+        damaged_bodies.push((ev.receiver, ev.position));
+
+        // glancing hits only scratch, hull hits harm
         sound_effects_to_play.push(SoundEffectEvent {
-            name: SoundEffectNames::Scratch,
+            name: if hull_damage > 0 {
+                SoundEffectNames::PhysicalHarm
+            } else {
+                SoundEffectNames::Scratch
+            },
         });
-        if health.hp <= 0 {
-            killed_bodies.push(*receiver);
+
+        let was_alive = health.hp > 0;
+        health.hp -= hull_damage;
+        if was_alive && health.hp <= 0 {
+            killed_bodies.push(ev.receiver);
         }
     }
 
     // cleanup events
-    for (ent, _rcvr, _dmg) in apply_damage.into_iter() {
+    for (ent, _ev) in apply_damage.into_iter() {
         world.despawn(ent);
     }
 
+    let damage_time = now();
+    for (damaged_body, position) in damaged_bodies {
+        world.insert_one(
+            damaged_body,
+            DamageOverlayCpt {
+                last_damage_time: damage_time,
+            },
+        );
+        if let Ok(mut emitters) = world.get::<&mut ParticleEmitterCpt>(damaged_body) {
+            emitters.hits.push(position);
+        }
+    }
+    world.spawn_batch(damage_numbers);
+
     for killed_body in killed_bodies {
         if world.get::<(&HumanInputCpt)>(killed_body).is_ok() {
             sound_effects_to_play.push(SoundEffectEvent {
@@ -514,6 +1322,7 @@ pub fn system_physical_damage_resolution(world: &mut World) {
                 name: SoundEffectNames::PhysicalDeath,
             });
         }
+        spawn_death_effect(world, killed_body);
         world.despawn(killed_body);
     }
 
@@ -522,27 +1331,90 @@ pub fn system_physical_damage_resolution(world: &mut World) {
     }
 }
 
-// TODO this could be a animation dispatcher, just like the render body system match block
-pub fn system_render_pings(world: &mut World, frame: &mut [u8]) {
-    for (ent, (pingdraw, colorbody, animation, transform)) in world.query_mut::<(
-        &PingDrawCpt,
-        &ColorBodyCpt,
-        &mut AnimationCpt,
+// bodies with a DeathEffectCpt leave pieces behind, spawned in the same tick as
+// their death sound
+fn spawn_death_effect(world: &mut World, killed_body: Entity) {
+    let Ok((death, transform, rigidbody, drawbody)) = world.query_one_mut::<(
+        &DeathEffectCpt,
         &TransformCpt,
+        Option<&RigidBodyCpt>,
+        &DrawBodyCpt,
+    )>(killed_body) else {
+        return;
+    };
+    let velocity = rigidbody.map_or(Vec2::zeros(), |rb| rb.velocity);
+    let (fragments, debris, flash) = gen_death_effect(death, transform, velocity, drawbody);
+    world.spawn_batch(fragments);
+    world.spawn_batch(debris);
+    if let Some(flash) = flash {
+        world.spawn(flash);
+    }
+}
+
+// health/shield bars over recently damaged bodies and rising damage numbers
+pub fn system_render_damage_overlay(world: &mut World, canvas: &mut Canvas) {
+    for (_id, (overlay, health, shield, transform, collider)) in world.query_mut::<(
+        &DamageOverlayCpt,
+        &HealthCpt,
+        Option<&ShieldCpt>,
+        &TransformCpt,
+        Option<&CircleColliderCpt>,
     )>() {
-        let mut current_frame = animation.current_frame;
-        let frame_count = animation.frame_count;
-        draw_arcs(
-            frame,
-            transform.position.x as i32,
-            transform.position.y as i32,
-            (pingdraw.r + pingdraw.r * (current_frame as f32 * 0.5)) as i32,
-            colorbody.primary,
-            pingdraw.gap_factors[current_frame],
+        let fade = 1.
+            - since(overlay.last_damage_time).as_secs_f32()
+                / DamageOverlayCpt::DURATION.as_secs_f32();
+        let r = collider.map_or(10., |c| c.r);
+        let shield_fraction = shield.map(|s| s.strength / s.max_strength);
+        draw_health_bar(
+            canvas,
+            transform.position,
+            r,
+            health.hp as f32 / health.max_hp as f32,
+            shield_fraction,
+            fade,
+        );
+    }
+
+    for (_id, (number, transform)) in world.query_mut::<(&DamageNumberCpt, &TransformCpt)>() {
+        let t = since(number.start_time).as_secs_f32();
+        let fade = 1. - t / DamageNumberCpt::DURATION.as_secs_f32();
+        let text = number.value.to_string();
+        draw_text(
+            canvas,
+            transform.position.x.round() as i32 - text_size(&text, 1).0 / 2,
+            (transform.position.y - DamageNumberCpt::RISE_SPEED * t).round() as i32,
+            &text,
+            1,
+            number.color.faded(fade),
+        );
+    }
+}
+
+// effects dispatch on their kind, just like the render body match block
+pub fn system_render_effects(world: &mut World, canvas: &mut Canvas) {
+    for (_ent, (effect, colorbody, animation, transform)) in
+        world.query_mut::<(&EffectCpt, &ColorBodyCpt, &AnimationCpt, &TransformCpt)>()
+    {
+        draw_effect(
+            canvas,
+            transform,
+            effect,
+            colorbody,
+            animation.current_frame,
+            animation.progress(),
         );
     }
 }
 
+// AnimationCpt drives the frame of sprite bodies
+pub fn system_sprite_animation(world: &mut World) {
+    for (_id, (drawbody, animation)) in world.query_mut::<(&mut DrawBodyCpt, &AnimationCpt)>() {
+        if let DrawData::Sprite { frame, .. } = &mut drawbody.data {
+            *frame = animation.current_frame;
+        }
+    }
+}
+
 pub fn system_animation_lifecycle(world: &mut World, dt: Dt) {
     let mut expired_anim_ents = vec![];
 
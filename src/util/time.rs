@@ -1,9 +1,37 @@
+use std::cell::Cell;
 use std::collections::VecDeque;
 use std::iter::Sum;
 use std::time;
 
 const N_FRAME_LOGS: usize = 200;
 
+// Game state reads the clock through now() and since() instead of Instant, so
+// timers and cooldowns can be frozen: a frozen clock only moves when advanced,
+// tests step it along with each update's Dt
+thread_local! {
+    static FROZEN_CLOCK: Cell<Option<time::Instant>> = const { Cell::new(None) };
+}
+
+pub fn now() -> time::Instant {
+    FROZEN_CLOCK
+        .with(|clock| clock.get())
+        .unwrap_or_else(time::Instant::now)
+}
+
+pub fn since(earlier: time::Instant) -> time::Duration {
+    now().saturating_duration_since(earlier)
+}
+
+#[cfg(test)]
+pub fn freeze_clock() {
+    FROZEN_CLOCK.with(|clock| clock.set(Some(time::Instant::now())));
+}
+
+#[cfg(test)]
+pub fn advance_frozen_clock(by: time::Duration) {
+    FROZEN_CLOCK.with(|clock| clock.set(clock.get().map(|t| t + by)));
+}
+
 pub struct Dt(pub time::Duration);
 impl Dt {
     pub fn secs(&self) -> f32 {
@@ -0,0 +1,155 @@
+use std::time;
+
+use crate::{
+    components::ProjectileKind,
+    gfx::pixel::{Color, CYAN, ORANGE, RED, WHITE, YELLOW},
+};
+
+// Weapon definitions
+// - a weapon is pure data: how it fires (pattern), what it fires (projectile
+//   archetype) and what it looks like. What it sounds like follows from the
+//   projectile, see audio::projectile_sounds
+// - emitters only hold a WeaponKind, the emission system looks up the spec
+// - the primary fire of a craft may be any equippable weapon, see audio.rs
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FirePattern {
+    // one projectile straight ahead
+    Single,
+    // n projectiles fanned evenly across arc (radians), centered on heading
+    Spread { n: u32, arc: f32 },
+    // n projectiles in quick succession, interval in ms between shots
+    Burst { n: u32, interval: i32 },
+    // hold to charge, fires on release. Damage and speed scale linearly up to
+    // max_factor once held for max_charge ms
+    Charge { max_charge: i32, max_factor: f32 },
+    // hitscan while held, deals dps in hit_damage sized chunks to the first
+    // collider along heading within range
+    Beam { dps: f32, range: f32 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Weapon {
+    pub name: &'static str,
+    pub pattern: FirePattern,
+    pub projectile: ProjectileKind,
+    pub color: Color,
+    pub cooldown: i32, // ms
+    pub projectile_speed: f32,
+    pub projectile_duration: time::Duration,
+    pub hit_damage: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum WeaponKind {
+    #[default]
+    PhotonCannon,
+    Scattergun,
+    BurstRifle,
+    RapidFire,
+    ChargeCannon,
+    MissileLauncher,
+    LaserBeam,
+}
+
+impl WeaponKind {
+    pub const ALL: [WeaponKind; 7] = [
+        WeaponKind::PhotonCannon,
+        WeaponKind::Scattergun,
+        WeaponKind::BurstRifle,
+        WeaponKind::RapidFire,
+        WeaponKind::ChargeCannon,
+        WeaponKind::MissileLauncher,
+        WeaponKind::LaserBeam,
+    ];
+
+    // cycle order for weapon switching
+    pub fn next(&self) -> WeaponKind {
+        let i = Self::ALL.iter().position(|k| k == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    pub fn spec(&self) -> Weapon {
+        match *self {
+            WeaponKind::PhotonCannon => Weapon {
+                name: "photon cannon",
+                pattern: FirePattern::Single,
+                projectile: ProjectileKind::Photon,
+                color: RED,
+                cooldown: 100,
+                projectile_speed: 300.,
+                projectile_duration: time::Duration::new(3, 0),
+                hit_damage: 50,
+            },
+            WeaponKind::Scattergun => Weapon {
+                name: "scattergun",
+                pattern: FirePattern::Spread {
+                    n: 5,
+                    arc: nalgebra_glm::pi::<f32>() / 6.,
+                },
+                projectile: ProjectileKind::Pellet,
+                color: ORANGE,
+                cooldown: 500,
+                projectile_speed: 350.,
+                projectile_duration: time::Duration::new(1, 0),
+                hit_damage: 15,
+            },
+            WeaponKind::BurstRifle => Weapon {
+                name: "burst rifle",
+                pattern: FirePattern::Burst { n: 3, interval: 60 },
+                projectile: ProjectileKind::Bolt,
+                color: CYAN,
+                cooldown: 400,
+                projectile_speed: 450.,
+                projectile_duration: time::Duration::new(2, 0),
+                hit_damage: 25,
+            },
+            WeaponKind::RapidFire => Weapon {
+                name: "rapid fire",
+                pattern: FirePattern::Single,
+                projectile: ProjectileKind::Flechette,
+                color: YELLOW,
+                cooldown: 40,
+                projectile_speed: 500.,
+                projectile_duration: time::Duration::new(1, 500_000_000),
+                hit_damage: 8,
+            },
+            WeaponKind::ChargeCannon => Weapon {
+                name: "charge cannon",
+                pattern: FirePattern::Charge {
+                    max_charge: 1500,
+                    max_factor: 4.,
+                },
+                projectile: ProjectileKind::Plasma,
+                color: Color::RGB(160, 32, 240),
+                cooldown: 300,
+                projectile_speed: 200.,
+                projectile_duration: time::Duration::new(4, 0),
+                hit_damage: 40,
+            },
+            WeaponKind::MissileLauncher => Weapon {
+                name: "missile launcher",
+                pattern: FirePattern::Single,
+                projectile: ProjectileKind::Missile,
+                color: WHITE,
+                cooldown: 600,
+                projectile_speed: 220.,
+                projectile_duration: time::Duration::new(5, 0),
+                hit_damage: 60,
+            },
+            WeaponKind::LaserBeam => Weapon {
+                name: "laser beam",
+                pattern: FirePattern::Beam {
+                    dps: 120.,
+                    range: 600.,
+                },
+                projectile: ProjectileKind::Beam,
+                color: Color::RGB(255, 96, 96),
+                cooldown: 0,
+                projectile_speed: 0.,
+                projectile_duration: time::Duration::ZERO,
+                hit_damage: 10,
+            },
+        }
+    }
+}